@@ -0,0 +1,669 @@
+//! # BBoard Contract Definition
+//!
+//! The `Contract` implementation for `static/bboard`, shared by the binaries
+//! that deploy, call or maintain it, and the witnesses its circuits need.
+//! Calls replay the ledger operations of `contract/index.js`, answering
+//! `localSecretKey()` from the caller's private state.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::{Mutex, OnceLock};
+
+use async_trait::async_trait;
+use rand::{Rng, SeedableRng};
+use sha2::{Digest, Sha256};
+
+use crate::keys::KeyCache;
+use crate::witness::{PrivateState, PrivateStateStore, WitnessContext, WitnessProvider};
+
+use midnight_node_ledger_helpers::{
+    deserialize,
+    storage::HashMap as HashMapStorage,
+    stval,
+    AlignedValue,
+    ChargedState,
+    Contract,
+    ContractAddress,
+    ContractCallPrototype,
+    ContractDeploy,
+    ContractMaintenanceAuthority,
+    ContractOperation,
+    ContractState,
+    LedgerContext,
+    Op,
+    ResultModeGather,
+    ResultModeVerify,
+    Sp,
+    StateValue,
+    Transcripts,
+    VerifierKey, // Import VerifierKey
+};
+
+/// Directory holding the compiled BBoard artifacts (relative to project root).
+pub const BBOARD_DIR: &str = "static/bboard";
+
+/// Private-state entry holding the caller's BBoard secret key.
+pub const SECRET_KEY: &str = "secretKey";
+
+pub struct BBoardContract {
+    pub witnesses: Box<dyn WitnessProvider>,
+    /// Shared so the caller can commit the witnesses' changes once the call
+    /// is on chain (see `PrivateStateStore::commit`).
+    pub private_state: Arc<PrivateStateStore>,
+    /// Account whose private state answers the witnesses (see `witness::account_id`).
+    pub account: Option<String>,
+    /// Randomness for the witnesses; fresh entropy unless set with `with_rng`.
+    pub rng: Mutex<StdRng>,
+}
+
+/// BBoard's circuits, in the order their operations are deployed.
+pub const CIRCUITS: [&str; 2] = ["post", "takeDown"];
+
+static RESOLVER: OnceLock<Resolver> = OnceLock::new();
+static VERIFIER_KEYS: OnceLock<Vec<VerifierKey>> = OnceLock::new();
+
+/// The resolver for BBoard's keys, built on first use. Binaries that deploy
+/// or call BBoard get it before building, so `Contract::resolver` only ever
+/// reads the built one.
+pub fn get_resolver() -> Result<&'static Resolver, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(resolver) = RESOLVER.get() {
+        return Ok(resolver);
+    }
+    let resolver = KeyCache::from_env().contract_resolver("bboard", BBOARD_DIR)?;
+    Ok(RESOLVER.get_or_init(|| resolver))
+}
+
+/// The verifier keys of [`CIRCUITS`], read through the key cache (and
+/// checked against its checksums) on first use. Binaries that deploy BBoard
+/// get them before building, so `Contract::deploy` only reads the loaded ones.
+pub fn verifier_keys() -> Result<&'static [VerifierKey], Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(keys) = VERIFIER_KEYS.get() {
+        return Ok(keys);
+    }
+    let cache = KeyCache::from_env();
+    let mut keys = Vec::new();
+    for circuit in CIRCUITS {
+        let bytes = cache
+            .contract_verifier_key("bboard", Path::new(BBOARD_DIR), circuit)?
+            .ok_or_else(|| format!("No verifier key for BBoard circuit '{circuit}'"))?;
+        keys.push(
+            deserialize(&mut bytes.as_slice())
+                .map_err(|e| format!("{circuit}.verifier is not a verifier key: {e}"))?,
+        );
+    }
+    Ok(VERIFIER_KEYS.get_or_init(|| keys))
+}
+
+impl BBoardContract {
+    pub fn new() -> Self {
+        Self {
+            witnesses: Box::new(BBoardWitnesses),
+            private_state: Arc::new(PrivateStateStore::default()),
+            account: None,
+            rng: Mutex::new(StdRng::from_entropy()),
+        }
+    }
+
+    /// A BBoard whose calls are made by `account`.
+    pub fn for_account(account: String) -> Self {
+        Self {
+            account: Some(account),
+            ..Self::new()
+        }
+    }
+
+    /// Draws witness randomness (a first post's secret key) from `rng`, e.g.
+    /// one seeded from `--rng-seed`.
+    pub fn with_rng(self, rng: StdRng) -> Self {
+        Self {
+            rng: Mutex::new(rng),
+            ..self
+        }
+    }
+
+    /// Evaluates witness `name` for a call to `address`, staging any change
+    /// it makes to the account's private state.
+    pub fn eval_witness(
+        &self,
+        name: &str,
+        address: &ContractAddress,
+        ledger: &StateValue<DefaultDB>,
+    ) -> Result<AlignedValue, Box<dyn std::error::Error + Send + Sync>> {
+        let account = self
+            .account
+            .as_deref()
+            .ok_or("BBoard witnesses need an account (BBoardContract::for_account)")?;
+        let mut private_state = self.private_state.load(address, account)?;
+        let context = WitnessContext { address, ledger };
+        let mut rng = self.rng.lock().map_err(|_| "BBoard RNG lock poisoned")?;
+        let value = self
+            .witnesses
+            .witness(name, &context, &mut private_state, &mut rng)?;
+        self.private_state.stage(address, account, private_state)?;
+        Ok(value)
+    }
+
+    /// Checks that circuit `key` can be called on `state` by this BBoard's
+    /// account, and evaluates its witness once so private-state errors surface
+    /// here. `Contract` can't return errors, so the binary calls this before
+    /// building and the call itself treats a failed check as unreachable.
+    pub fn check_call(
+        &self,
+        key: &str,
+        address: &ContractAddress,
+        state: &ContractState<DefaultDB>,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if !CIRCUITS.contains(&key) {
+            return Err(format!("BBoard has no circuit '{key}'").into());
+        }
+        if state.operations.get(&key.as_bytes().into()).is_none() {
+            return Err(format!("Contract has no operation '{key}'").into());
+        }
+        let ledger = state.data.get();
+        let board = BBoardLedger::decode(&ledger)?;
+        let sk_value = self.eval_witness("localSecretKey", address, &ledger)?;
+        let sk = bytes32(
+            value_atoms(&sk_value)
+                .first()
+                .map_or(&[][..], Vec::as_slice),
+        );
+        match key {
+            "post" if board.state == BoardState::Occupied => {
+                Err("The board is occupied; take the post down first".into())
+            }
+            "takeDown" if board.state == BoardState::Vacant => {
+                Err("The board is empty; nothing to take down".into())
+            }
+            "takeDown" if board.owner != public_key(&sk, board.sequence) => {
+                Err("The current post was not made with this seed's secret key".into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// `publicKey(sk, sequence)` from `bboard.compact`: the persistent hash of
+/// `[pad(32, "bboard:pk:"), sequence as Bytes<32>, sk]`.
+pub fn public_key(sk: &[u8; 32], sequence: u64) -> [u8; 32] {
+    let mut prefix = [0u8; 32];
+    prefix[..10].copy_from_slice(b"bboard:pk:");
+    let mut sequence_bytes = [0u8; 32];
+    sequence_bytes[..8].copy_from_slice(&sequence.to_le_bytes());
+
+    let mut hasher = Sha256::new();
+    hasher.update(prefix);
+    hasher.update(sequence_bytes);
+    hasher.update(sk);
+    hasher.finalize().into()
+}
+
+// ─── Ledger state ────────────────────────────────────────────────────────────
+
+/// `enum State` from `bboard.compact`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoardState {
+    Vacant,
+    Occupied,
+}
+
+/// The public ledger of a BBoard contract: its four `export ledger` fields,
+/// stored as an array of cells in declaration order.
+#[derive(Debug, Clone)]
+pub struct BBoardLedger {
+    pub state: BoardState,
+    pub message: Option<String>,
+    pub sequence: u64,
+    pub owner: [u8; 32],
+}
+
+impl BBoardLedger {
+    pub fn decode(
+        ledger: &StateValue<DefaultDB>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let StateValue::Array(fields) = ledger else {
+            return Err("BBoard ledger state is not an array".into());
+        };
+        // A cell's atoms; `null` (an unset `message`) has none.
+        let atoms = |index: usize| -> Vec<Vec<u8>> {
+            match fields.get(index) {
+                Some(StateValue::Cell(value)) => value_atoms(value),
+                _ => Vec::new(),
+            }
+        };
+
+        let state = match atoms(0).first().map(|atom| atom_u64(atom)) {
+            None | Some(0) => BoardState::Vacant,
+            Some(1) => BoardState::Occupied,
+            Some(n) => return Err(format!("Unknown BBoard state {n}").into()),
+        };
+        // `Maybe<Opaque<"string">>` is `[is_some, value]`.
+        let message = match atoms(1).as_slice() {
+            [is_some, value, ..] if atom_u64(is_some) == 1 => {
+                Some(String::from_utf8_lossy(value).into_owned())
+            }
+            _ => None,
+        };
+        let sequence = atoms(2).first().map_or(0, |atom| atom_u64(atom));
+        let owner = bytes32(atoms(3).first().map_or(&[][..], Vec::as_slice));
+
+        Ok(Self {
+            state,
+            message,
+            sequence,
+            owner,
+        })
+    }
+}
+
+fn value_atoms(value: &AlignedValue) -> Vec<Vec<u8>> {
+    value.value.0.iter().map(|atom| atom.0.clone()).collect()
+}
+
+/// A `Bytes<32>` atom, restoring the trailing zero bytes atoms drop.
+fn bytes32(atom: &[u8]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    let len = atom.len().min(32);
+    bytes[..len].copy_from_slice(&atom[..len]);
+    bytes
+}
+
+/// Atoms are little-endian with trailing zero bytes dropped.
+fn atom_u64(atom: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    let len = atom.len().min(8);
+    bytes[..len].copy_from_slice(&atom[..len]);
+    u64::from_le_bytes(bytes)
+}
+
+// ─── Witnesses ───────────────────────────────────────────────────────────────
+
+/// `witness localSecretKey(): Bytes<32>`, backed by the private state.
+///
+/// The first call draws a key from the caller's RNG and stores it; later
+/// calls (e.g. the `takeDown` after a `post`) return the same key.
+pub struct BBoardWitnesses;
+
+impl WitnessProvider for BBoardWitnesses {
+    fn witness(
+        &self,
+        name: &str,
+        _context: &WitnessContext,
+        private_state: &mut PrivateState,
+        rng: &mut StdRng,
+    ) -> Result<AlignedValue, Box<dyn std::error::Error + Send + Sync>> {
+        match name {
+            "localSecretKey" => {
+                let sk = private_state
+                    .entry(SECRET_KEY.to_string())
+                    .or_insert_with(|| rng.gen::<[u8; 32]>().to_vec());
+                let sk: [u8; 32] = sk
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| "Stored BBoard secret key is not 32 bytes")?;
+                Ok(AlignedValue::from(sk))
+            }
+            _ => Err(format!("BBoard has no witness '{name}'").into()),
+        }
+    }
+}
+
+/// Reads and deserializes a verifier key file.
+pub fn load_verifier_key(
+    path: &str,
+) -> Result<VerifierKey, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+    deserialize(&mut bytes.as_slice())
+        .map_err(|e| format!("{path} is not a verifier key: {e}").into())
+}
+
+#[async_trait]
+impl Contract<DefaultDB> for BBoardContract {
+    async fn deploy(
+        &self,
+        committee: &[VerifyingKey],
+        committee_threshold: u32,
+        rng: &mut StdRng,
+    ) -> ContractDeploy<DefaultDB> {
+        let keys = verifier_keys().unwrap_or_else(|e| {
+            unreachable!("BBoard verifier keys are loaded before deploying: {e}")
+        });
+        let mut operations = HashMapStorage::new();
+        for (circuit, key) in CIRCUITS.iter().zip(keys) {
+            operations = operations.insert(
+                circuit.as_bytes().into(),
+                ContractOperation::new(Some(key.clone())),
+            );
+        }
+
+        // The ledger `contract/index.js` initializes: a vacant board, no
+        // message, sequence 1 and a zeroed owner.
+
+        let initial_state = stval!([
+            (0u64),      // state = VACANT
+            null,        // message = None
+            (1u64),      // sequence = 1
+            ([0u8; 32])  // owner = 32 bytes
+        ]);
+
+        let contract = ContractState {
+            data: ChargedState::new(initial_state),
+            operations,
+            maintenance_authority: ContractMaintenanceAuthority {
+                committee: committee.to_vec(),
+                threshold: committee_threshold,
+                counter: 0,
+            },
+            balance: HashMapStorage::new(),
+        };
+
+        ContractDeploy::new(rng, contract)
+    }
+
+    fn resolver(&self) -> &'static Resolver {
+        get_resolver()
+            .unwrap_or_else(|e| unreachable!("BBoard's resolver is built before building: {e}"))
+    }
+
+    /// Runs circuit `key` against the contract's current state, returning
+    /// its output, the witness values it read and its public transcripts.
+    /// `localSecretKey()` comes from `self.eval_witness(..)`.
+    fn transcript(
+        &self,
+        key: &str,
+        input: &Box<dyn Any + Send + Sync>,
+        address: &ContractAddress,
+        context: Arc<LedgerContext<DefaultDB>>,
+    ) -> (AlignedValue, Vec<AlignedValue>, Vec<Transcripts<DefaultDB>>) {
+        let state = contract_state(address, &context);
+        let ledger = state.data.get();
+        let board = BBoardLedger::decode(&ledger)
+            .unwrap_or_else(|e| unreachable!("BBoard state is checked before calling: {e}"));
+        let sk_value = self
+            .eval_witness("localSecretKey", address, &ledger)
+            .unwrap_or_else(|e| unreachable!("localSecretKey() is checked before calling: {e}"));
+        let sk = bytes32(
+            value_atoms(&sk_value)
+                .first()
+                .map_or(&[][..], Vec::as_slice),
+        );
+
+        let mut program = Vec::new();
+        let output = match key {
+            "post" => {
+                if board.state != BoardState::Vacant {
+                    unreachable!("post to an occupied board; checked before calling");
+                }
+                let owner = public_key(&sk, board.sequence);
+                program.extend(read_field(0, false));
+                program.extend(read_field(2, true));
+                program.extend(write_field(3, AlignedValue::from(owner)));
+                program.extend(write_field(1, maybe_string(Some(message(input)))));
+                program.extend(write_field(
+                    0,
+                    AlignedValue::from(BoardState::Occupied as u8),
+                ));
+                AlignedValue::concat([])
+            }
+            "takeDown" => {
+                if board.state != BoardState::Occupied
+                    || board.owner != public_key(&sk, board.sequence)
+                {
+                    unreachable!(
+                        "takeDown of a post this account doesn't own; checked before calling"
+                    );
+                }
+                program.extend(read_field(0, false));
+                program.extend(read_field(3, false));
+                program.extend(read_field(2, true));
+                program.extend(read_field(1, false));
+                program.extend(write_field(0, AlignedValue::from(BoardState::Vacant as u8)));
+                program.extend(increment_field(2, 1));
+                program.extend(write_field(1, maybe_string(None)));
+                opaque_string(board.message.as_deref().unwrap_or_default())
+            }
+            _ => unreachable!("BBoard has no circuit '{key}'; checked before calling"),
+        };
+
+        let query = QueryContext::new(state.data.clone(), *address);
+        let results = query
+            .query(&program, None, &INITIAL_COST_MODEL)
+            .unwrap_or_else(|e| {
+                unreachable!("{key} transcript rejected by a checked state: {e:?}")
+            });
+        let reads: Vec<AlignedValue> = results
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                GatherEvent::Read(value) => Some(value.clone()),
+                _ => None,
+            })
+            .collect();
+        let transcripts = partition_transcripts(
+            &[PreTranscript {
+                context: query,
+                program: Self::program_with_results(&program, &reads),
+                comm_comm: None,
+            }],
+            &INITIAL_PARAMETERS,
+        )
+        .unwrap_or_else(|e| unreachable!("{key} transcript cannot be partitioned: {e:?}"));
+
+        (output, vec![sk_value], transcripts)
+    }
+
+    fn operation(
+        &self,
+        key: &str,
+        address: &ContractAddress,
+        context: Arc<LedgerContext<DefaultDB>>,
+    ) -> Sp<ContractOperation, DefaultDB> {
+        contract_state(address, &context)
+            .operations
+            .get(&key.as_bytes().into())
+            .unwrap_or_else(|| {
+                unreachable!("BBoard has no operation '{key}'; checked before calling")
+            })
+    }
+
+    /// Fills each `popeq` of `prog` with the value it read, in order.
+    fn program_with_results(
+        prog: &[Op<ResultModeGather, DefaultDB>],
+        results: &[AlignedValue],
+    ) -> Vec<Op<ResultModeVerify, DefaultDB>> {
+        let mut results = results.iter().cloned();
+        prog.iter()
+            .map(|op| {
+                op.clone().translate(|()| {
+                    results
+                        .next()
+                        .unwrap_or_else(|| unreachable!("a result is gathered for every popeq"))
+                })
+            })
+            .collect()
+    }
+
+    fn contract_call(
+        &self,
+        address: &ContractAddress,
+        key: &'static str,
+        input: &Box<dyn Any + Send + Sync>,
+        rng: &mut StdRng,
+        context: Arc<LedgerContext<DefaultDB>>,
+    ) -> ContractCallPrototype<DefaultDB> {
+        let (output, private_transcript_outputs, transcripts) =
+            self.transcript(key, input, address, context.clone());
+        let (guaranteed_public_transcript, fallible_public_transcript) =
+            transcripts.into_iter().next().unwrap_or((None, None));
+        let input = match key {
+            "post" => opaque_string(message(input)),
+            _ => AlignedValue::concat([]),
+        };
+
+        ContractCallPrototype {
+            address: *address,
+            entry_point: key.as_bytes().into(),
+            op: (*self.operation(key, address, context)).clone(),
+            guaranteed_public_transcript,
+            fallible_public_transcript,
+            private_transcript_outputs,
+            input,
+            output,
+            communication_commitment_rand: rng.gen(),
+            key_location: KeyLocation(Cow::Borrowed(key)),
+        }
+    }
+}
+
+// ─── Circuit programs ────────────────────────────────────────────────────────
+//
+// The ledger reads and writes of `post` and `takeDown`, as `contract/index.js`
+// issues them. Fields are addressed by their index in the ledger array.
+
+fn contract_state(
+    address: &ContractAddress,
+    context: &LedgerContext<DefaultDB>,
+) -> ContractState<DefaultDB> {
+    context
+        .ledger_state
+        .lock()
+        .expect("ledger state lock poisoned")
+        .index(*address)
+        .unwrap_or_else(|| {
+            unreachable!(
+                "No contract at {}; its state is fetched before calling",
+                hex::encode(address.0 .0)
+            )
+        })
+}
+
+/// `post`'s message, passed to the call as a `String`.
+fn message(input: &Box<dyn Any + Send + Sync>) -> &str {
+    input
+        .downcast_ref::<String>()
+        .unwrap_or_else(|| unreachable!("post takes its message as a String"))
+}
+
+fn field_key(index: u8) -> Key {
+    Key::Value(AlignedValue::from(index))
+}
+
+fn cell(value: AlignedValue) -> StateValue<DefaultDB> {
+    StateValue::Cell(Sp::new(value))
+}
+
+/// Reads field `index`; `cached` when an earlier op of the call read it.
+fn read_field(index: u8, cached: bool) -> [Op<ResultModeGather, DefaultDB>; 3] {
+    [
+        Op::Dup { n: 0 },
+        Op::Idx {
+            cached: false,
+            push_path: false,
+            path: vec![field_key(index)].into(),
+        },
+        Op::Popeq { cached, result: () },
+    ]
+}
+
+fn write_field(index: u8, value: AlignedValue) -> [Op<ResultModeGather, DefaultDB>; 3] {
+    [
+        Op::Push {
+            storage: false,
+            value: cell(AlignedValue::from(index)),
+        },
+        Op::Push {
+            storage: true,
+            value: cell(value),
+        },
+        Op::Ins {
+            cached: false,
+            n: 1,
+        },
+    ]
+}
+
+/// `field.increment(by)` on a `Counter`.
+fn increment_field(index: u8, by: u32) -> [Op<ResultModeGather, DefaultDB>; 3] {
+    [
+        Op::Idx {
+            cached: false,
+            push_path: true,
+            path: vec![field_key(index)].into(),
+        },
+        Op::Addi { immediate: by },
+        Op::Ins { cached: true, n: 1 },
+    ]
+}
+
+/// An `Opaque<"string">`.
+fn opaque_string(s: &str) -> AlignedValue {
+    AlignedValue::new(
+        Value(vec![ValueAtom(s.as_bytes().to_vec()).normalize()]),
+        Alignment::singleton(AlignmentAtom::Compress),
+    )
+    .unwrap_or_else(|| unreachable!("an opaque atom matches the compress alignment"))
+}
+
+/// A `Maybe<Opaque<"string">>`: `[is_some, value]`, with `""` for `none`.
+fn maybe_string(value: Option<&str>) -> AlignedValue {
+    AlignedValue::concat([
+        &AlignedValue::from(value.is_some()),
+        &opaque_string(value.unwrap_or_default()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seeded(seed: u8) -> StdRng {
+        StdRng::from_seed([seed; 32])
+    }
+
+    fn secret_key(private_state: &mut PrivateState, rng: &mut StdRng) -> AlignedValue {
+        let ledger = StateValue::Null;
+        let address = ContractAddress(HashOutput([0; 32]));
+        let context = WitnessContext {
+            address: &address,
+            ledger: &ledger,
+        };
+        BBoardWitnesses
+            .witness("localSecretKey", &context, private_state, rng)
+            .unwrap()
+    }
+
+    #[test]
+    fn first_secret_key_follows_the_rng() {
+        let first = secret_key(&mut PrivateState::new(), &mut seeded(1));
+        assert_eq!(first, secret_key(&mut PrivateState::new(), &mut seeded(1)));
+        assert_ne!(first, secret_key(&mut PrivateState::new(), &mut seeded(2)));
+    }
+
+    #[test]
+    fn stored_secret_key_is_reused() {
+        let mut private_state = PrivateState::new();
+        let first = secret_key(&mut private_state, &mut seeded(1));
+        assert_eq!(first, secret_key(&mut private_state, &mut seeded(2)));
+    }
+
+    /// The deploy `deploy_contract --rng-seed` builds is the same every run.
+    #[tokio::test]
+    async fn seeded_deploy_is_byte_identical() {
+        verifier_keys().unwrap();
+        let build = || async {
+            let deploy = BBoardContract::new().deploy(&[], 0, &mut seeded(1)).await;
+            serialize(&deploy).unwrap()
+        };
+        let first = build().await;
+        assert_eq!(first, build().await);
+
+        let other = BBoardContract::new().deploy(&[], 0, &mut seeded(2)).await;
+        assert_ne!(first, serialize(&other).unwrap());
+    }
+}
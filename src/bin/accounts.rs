@@ -0,0 +1,72 @@
+//! # Midnight Accounts
+//!
+//! Shows the Substrate accounts that sign extrinsics (see `signer.rs`) and
+//! manages a keystore of them.
+//!
+//! ## Usage:
+//! ```text
+//! accounts dev [--scheme ed25519]                 # dev accounts and their nonces
+//! accounts show [--suri <uri>] [--scheme ...]      # one account and its nonce
+//! accounts list --keystore <dir>                   # keys in a keystore
+//! accounts insert <suri> --keystore <dir> [--scheme ...]
+//! ```
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../signer.rs"]
+mod signer;
+
+use signer::{Account, Keystore, Scheme};
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Accounts ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let scheme: Scheme = args.value_or("scheme", "sr25519").parse()?;
+
+    match args.subcommand() {
+        Some("list") => {
+            let keystore = Keystore::new(args.require("keystore")?);
+            for public in keystore.list()? {
+                println!("  0x{}", hex::encode(public));
+            }
+        }
+        Some("insert") => {
+            let suri = args.positional(1).ok_or("Missing secret URI")?;
+            let account = Keystore::new(args.require("keystore")?).insert(scheme, suri)?;
+            println!("✓ Stored {:?} key", account.scheme());
+            print_account(&account, None);
+        }
+        Some("show") => {
+            let (api, _rpc) = chain::connect(NODE_URL).await?;
+            let account = Account::from_args(&args)?;
+            print_account(&account, Some(account.nonce(&api).await?));
+        }
+        None | Some("dev") => {
+            let (api, _rpc) = chain::connect(NODE_URL).await?;
+            for name in signer::DEV_ACCOUNTS {
+                let account = Account::parse(scheme, name)?;
+                println!("{name}:");
+                print_account(&account, Some(account.nonce(&api).await?));
+            }
+        }
+        Some(other) => return Err(format!("Unknown subcommand {other}").into()),
+    }
+    Ok(())
+}
+
+fn print_account(account: &Account, nonce: Option<u64>) {
+    println!("  Address:    {}", account.account_id());
+    println!("  Public key: 0x{}", hex::encode(account.public_key()));
+    if let Some(nonce) = nonce {
+        println!("  Nonce:      {nonce}");
+    }
+}
@@ -0,0 +1,194 @@
+//! # Midnight Contract Call (BBoard)
+//!
+//! Calls a deployed BBoard's `post` or `takeDown` circuit. The caller's
+//! `localSecretKey()` witness is answered from their private state (see
+//! `witness.rs`), so the seed that posted can take the post down in a later
+//! run.
+//!
+//! ## Usage:
+//! ```text
+//! call_contract <name|address> post <message> [--seed <hex>]
+//! call_contract <name|address> take-down      [--seed <hex>]
+//!               [--proof-server <url> | --mock-proofs | --build-only <file>]
+//!               [--rng-seed <seed>] [--verify-root-every <n>]
+//! ```
+//!
+//! The seed pays the fees and identifies the caller's private state. Tokens
+//! the circuit moves in or out of the contract (see `tokens.rs`) are balanced
+//! against the same wallet; BBoard's circuits move none.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../bboard.rs"]
+mod bboard;
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../keys.rs"]
+mod keys;
+#[path = "../manifest.rs"]
+mod manifest;
+#[path = "../prover.rs"]
+mod prover;
+#[path = "../rng.rs"]
+mod rng;
+#[path = "../tokens.rs"]
+mod tokens;
+#[path = "../txfile.rs"]
+mod txfile;
+#[path = "../witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::contract::{BuildContractAction, ContractCallInfo};
+use midnight_node_ledger_helpers::*;
+
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use bboard::{BBoardContract, BBoardLedger};
+use midnight::api::midnight::events::ContractCall as ContractCallEvent;
+use tokens::TokenEffects;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// Wallet seed (hex-encoded, 32 bytes). Pays the fees.
+const WALLET_SEED_HEX: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    keys::KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Contract Call (BBoard) ===\n");
+
+    let args = cli::Args::from_env(prover::SWITCHES);
+    let contract = args.positional(0).ok_or("Missing <name|address>")?;
+    let (key, input): (&'static str, Box<dyn Any + Send + Sync>) = match args.positional(1) {
+        Some("post") => {
+            let message = args.positional(2).ok_or("Missing <message>")?;
+            ("post", Box::new(message.to_string()))
+        }
+        Some("take-down") => ("takeDown", Box::new(())),
+        _ => return Err("Usage: call_contract <name|address> <post|take-down> ...".into()),
+    };
+    let seed: WalletSeed = args
+        .value_or("seed", WALLET_SEED_HEX)
+        .parse()
+        .map_err(|e| format!("Invalid --seed: {e:?}"))?;
+    let account = witness::account_id(&seed);
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+    let network_id = chain::fetch_network_id(&api).await?;
+    println!("✓ Network ID: {network_id}");
+    chain::ensure_ledger_compatible(&api).await?;
+
+    // ── Check the call can succeed before syncing ────────────────────────
+    let address = manifest::resolve_contract(&api, contract).await?;
+    let state = chain::fetch_contract_state(&api, &address).await?;
+    println!("  Contract: {}", hex::encode(address.0 .0));
+    print_board(&BBoardLedger::decode(&state.data.get())?);
+
+    let rng_seed = rng::seed_from_args(&args);
+    let bboard = BBoardContract::for_account(account).with_rng(rng::rng(rng_seed, "witnesses"));
+    bboard.check_call(key, &address, &state)?;
+    // Witness changes (a first post's secret key) are kept until the call is on chain
+    let private_state = bboard.private_state.clone();
+
+    // ── Sync and build the call ──────────────────────────────────────────
+    let context = Arc::new(LedgerContext::<DefaultDB>::new_from_wallet_seeds(
+        &network_id,
+        &[seed],
+    ));
+    chain::sync(&api, &rpc, &context, &chain::SyncOptions::from_args(&args)?).await?;
+
+    println!("\n=== Transaction Builder (Contract Call: {key}) ===");
+    // `Contract::resolver` can't fail, so build it up front
+    bboard::get_resolver()?;
+
+    let movements = bboard.token_movements(key, &*input);
+    let funding = tokens::fund_call(&movements, seed, address, &context, &state)?;
+    for movement in &movements {
+        println!(
+            "  {:?}: {} {}",
+            movement.direction,
+            movement.amount,
+            tokens::token_type_hex(&movement.token_type)
+        );
+    }
+
+    let call: Box<dyn BuildContractAction<DefaultDB>> = Box::new(ContractCallInfo {
+        type_: bboard,
+        address,
+        key,
+        input,
+        _marker: PhantomData,
+    });
+
+    let intent_info = IntentInfo {
+        guaranteed_unshielded_offer: funding.unshielded_offer,
+        fallible_unshielded_offer: None,
+        actions: vec![call],
+    };
+
+    let prover = prover::from_args(&args, &network_id).await?;
+    let mut tx_info = StandardTrasactionInfo::new_from_context(
+        context.clone(),
+        prover.provider.clone(),
+        rng_seed,
+    );
+    tx_info.add_intent(1, Box::new(intent_info));
+    tx_info.set_guaranteed_offer(funding.guaranteed_offer);
+    tx_info.set_funding_seeds(vec![seed]);
+    tx_info.use_mock_proofs_for_fees(true);
+
+    println!("\nProving transaction...");
+    let proven_tx = match prover.prove(&mut tx_info).await? {
+        prover::Outcome::Proven(tx) => tx,
+        // Continued by `offline_tx prove` and `offline_tx submit`, which need
+        // the secret key saved for a later take-down
+        prover::Outcome::Exported(_) => return private_state.commit(),
+    };
+    println!("✓ Transaction proven");
+
+    let serialized = serialize(&proven_tx)?;
+    println!("✓ Serialized ({} bytes)", serialized.len());
+
+    let Some(submitted) = chain::submit_transaction(&api, serialized).await? else {
+        return Err("Call failed on chain; private state left unchanged".into());
+    };
+    private_state.commit()?;
+
+    let confirmed = submitted
+        .events
+        .find::<ContractCallEvent>()
+        .filter_map(Result::ok)
+        .any(|ev| chain::decode_event_contract_address(&ev.0.contract_address) == Some(address));
+    if !confirmed {
+        println!("⚠️  No ContractCall event emitted");
+    }
+
+    let state = chain::fetch_contract_state(&api, &address).await?;
+    println!("\n=== Board after the call ===");
+    print_board(&BBoardLedger::decode(&state.data.get())?);
+
+    Ok(())
+}
+
+// BBoard's circuits neither claim nor emit tokens.
+impl TokenEffects for BBoardContract {}
+
+fn print_board(board: &BBoardLedger) {
+    println!("  State:    {:?}", board.state);
+    println!("  Sequence: {}", board.sequence);
+    if let Some(message) = &board.message {
+        println!("  Message:  {message}");
+        println!("  Owner:    {}", hex::encode(board.owner));
+    }
+}
@@ -0,0 +1,63 @@
+//! # Midnight Chain Info
+//!
+//! Reports the node's network id, runtime version, ledger version, ledger
+//! parameters and zswap state root at the latest block, and whether this
+//! build's ledger helpers can build transactions for it.
+//!
+//! ## Usage:
+//! ```text
+//! chain_info
+//! ```
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+
+use chain::ChainInfo;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Chain Info ===\n");
+
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let info = ChainInfo::fetch(&api).await?;
+
+    println!("\n=== Chain ===");
+    println!(
+        "  Block:               {} (0x{})",
+        info.block_number,
+        hex::encode(info.block_hash.0)
+    );
+    println!("  Network ID:          {}", info.network_id);
+    println!("  Spec version:        {}", info.spec_version);
+    println!("  Transaction version: {}", info.transaction_version);
+
+    println!("\n=== Ledger ===");
+    println!("  Version:    {}", info.ledger_version);
+    println!("  Zswap root: {}", hex::encode(&info.zswap_root));
+
+    let params = &info.ledger_parameters;
+    println!("\n=== Ledger Parameters ===");
+    println!("  Fee prices: {:#?}", params.fee_prices);
+    println!("  Limits:     {:#?}", params.limits);
+    println!("  DUST:       {:#?}", params.dust);
+
+    println!();
+    match info.check_ledger_compatible() {
+        Ok(()) => println!(
+            "✅ Compatible with this build (ledger {}.x)",
+            chain::SUPPORTED_LEDGER_MAJOR
+        ),
+        Err(e) => println!("⚠️  {e}"),
+    }
+
+    Ok(())
+}
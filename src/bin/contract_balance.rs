@@ -0,0 +1,60 @@
+//! # Midnight Contract Balances
+//!
+//! Shows the tokens held by a contract, read from `ContractState.balance`
+//! through the `get_contract_state` runtime API.
+//!
+//! ## Usage:
+//! ```text
+//! contract_balance <name|address> [--token <token type>]
+//! ```
+//!
+//! A token type is `shielded:<hex>`, `unshielded:<hex>` or `dust`, as the
+//! balances are printed; bare hex is a shielded token type.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../manifest.rs"]
+mod manifest;
+#[path = "../tokens.rs"]
+mod tokens;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Contract Balances ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let contract = args
+        .positional(0)
+        .ok_or("Usage: contract_balance <name|address>")?;
+
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let address = manifest::resolve_contract(&api, contract).await?;
+    println!("  Contract: {}", hex::encode(address.0 .0));
+
+    if let Some(token) = args.value("token") {
+        let token_type = tokens::parse_token_type(token)?;
+        let amount = tokens::fetch_contract_balance(&api, &address, token_type).await?;
+        println!("  {}: {amount}", tokens::token_type_hex(&token_type));
+        return Ok(());
+    }
+
+    let state = chain::fetch_contract_state(&api, &address).await?;
+    let balances = tokens::contract_balances(&state);
+    if balances.is_empty() {
+        println!("  (no tokens held)");
+    }
+    for (token_type, amount) in balances {
+        println!("  {}: {amount}", tokens::token_type_hex(&token_type));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,270 @@
+//! # Midnight Genesis
+//!
+//! Shows what the chain started from: every system transaction in block 0
+//! (classified by `systx.rs`), the initial NIGHT allocations, DUST
+//! registrations and ledger parameters. Also reads the runtime's genesis
+//! presets and diffs the genesis sections of two chain spec files (raw
+//! specs only as a key-level hex diff).
+//!
+//! ## Usage:
+//! ```text
+//! genesis [show]
+//! genesis presets
+//! genesis preset [<name>] [--out <file>]
+//! genesis diff <spec-a.json> <spec-b.json>
+//! ```
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../systx.rs"]
+mod systx;
+
+use midnight_node_ledger_helpers::*;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use chain::RawTransaction;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Genesis ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    match args.subcommand() {
+        None | Some("show") => {
+            let (api, rpc) = chain::connect(NODE_URL).await?;
+            show(&api, &rpc).await
+        }
+        Some("presets") => {
+            let (api, _rpc) = chain::connect(NODE_URL).await?;
+            let names = api
+                .runtime_api()
+                .at_latest()
+                .await?
+                .call(midnight::api::apis().genesis_builder().preset_names())
+                .await?;
+            if names.is_empty() {
+                println!("  (the runtime has no named presets)");
+            }
+            for name in names {
+                println!("  {name}");
+            }
+            Ok(())
+        }
+        Some("preset") => {
+            let (api, _rpc) = chain::connect(NODE_URL).await?;
+            let name = args.positional(1).map(str::to_string);
+            let json = api
+                .runtime_api()
+                .at_latest()
+                .await?
+                .call(
+                    midnight::api::apis()
+                        .genesis_builder()
+                        .get_preset(name.clone()),
+                )
+                .await?
+                .ok_or_else(|| {
+                    format!(
+                        "No genesis preset {}",
+                        name.as_deref().unwrap_or("(default)")
+                    )
+                })?;
+            let pretty = serde_json::to_string_pretty(&serde_json::from_slice::<Value>(&json)?)?;
+            match args.value("out") {
+                Some(out) => {
+                    std::fs::write(out, pretty)?;
+                    println!("✓ Written to {out}");
+                }
+                None => println!("{pretty}"),
+            }
+            Ok(())
+        }
+        Some("diff") => {
+            let (Some(a), Some(b)) = (args.positional(1), args.positional(2)) else {
+                return Err("Usage: genesis diff <spec-a.json> <spec-b.json>".into());
+            };
+            diff(a, b)
+        }
+        Some(other) => Err(format!(
+            "Unknown subcommand {other}; expected show, presets, preset or diff"
+        )
+        .into()),
+    }
+}
+
+// ─── Block 0 ─────────────────────────────────────────────────────────────────
+
+async fn show(
+    api: &chain::Api,
+    rpc: &chain::Rpc,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let block = chain::fetch_block(api, rpc, 0).await?;
+    println!("  Genesis hash: 0x{}", hex::encode(block.hash.0));
+
+    // Address → NIGHT allocated to it
+    let mut allocations: BTreeMap<String, u128> = BTreeMap::new();
+    let mut dust_registrations = Vec::new();
+    let mut parameters = None;
+
+    for block_tx in &block.transactions {
+        println!("\n=== Extrinsic {} ===", block_tx.extrinsic_index);
+        match &block_tx.raw {
+            RawTransaction::System(bytes) => {
+                let tx = deserialize::<SystemTransaction, _>(&mut bytes.as_slice())?;
+                let origin = systx::Origin::of(block.number, block_tx);
+                systx::print(&systx::classify(&tx, Some(origin)));
+
+                match &tx {
+                    SystemTransaction::DistributeNight(_, outputs) => {
+                        for out in outputs {
+                            *allocations
+                                .entry(hex::encode(out.target_address.0 .0))
+                                .or_default() += out.amount;
+                        }
+                    }
+                    SystemTransaction::CNightGeneratesDustUpdate { events } => {
+                        dust_registrations.extend(
+                            events
+                                .iter()
+                                .filter(|event| {
+                                    event.action == CNightGeneratesDustActionType::Create
+                                })
+                                .map(|event| format!("{:?} ({} cNight)", event.owner, event.value)),
+                        );
+                    }
+                    SystemTransaction::OverwriteParameters(params) => {
+                        parameters = Some(params.clone());
+                    }
+                    _ => {}
+                }
+            }
+            RawTransaction::Standard(bytes) => {
+                let tx = deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice())?;
+                println!(
+                    "  Standard transaction {}",
+                    hex::encode(tx.transaction_hash().0 .0)
+                );
+                if let Transaction::Standard(stx) = &tx {
+                    for (_, intent) in stx.intents.iter() {
+                        let Some(dust) = &intent.dust_actions else {
+                            continue;
+                        };
+                        dust_registrations.extend(dust.registrations.iter().map(|registration| {
+                            format!(
+                                "{:?} → {:?}",
+                                registration.night_key, registration.dust_address
+                            )
+                        }));
+                    }
+                }
+            }
+        }
+    }
+
+    println!("\n=== Allocations ===");
+    if allocations.is_empty() {
+        println!("  (none)");
+    }
+    for (address, amount) in &allocations {
+        println!("  {address}: {amount}");
+    }
+    println!("  Total: {}", allocations.values().sum::<u128>());
+
+    println!("\n=== DUST registrations ===");
+    if dust_registrations.is_empty() {
+        println!("  (none)");
+    }
+    for registration in &dust_registrations {
+        println!("  {registration}");
+    }
+
+    println!("\n=== Ledger parameters ===");
+    match parameters {
+        Some(params) => println!("  {params:#?}"),
+        None => println!("  (not overwritten at genesis; ledger defaults)"),
+    }
+    Ok(())
+}
+
+// ─── Chain spec diff ─────────────────────────────────────────────────────────
+
+/// Compares the `genesis` sections of two chain specs, key by key. For
+/// `runtimeGenesis` specs the keys are config paths; for raw specs they are
+/// the hex storage keys of `raw.top`, compared as opaque hex with no attempt
+/// to decode which pallet item a key or value belongs to.
+fn diff(a: &str, b: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let load =
+        |path: &str| -> Result<BTreeMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
+            let spec: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            let genesis = spec
+                .get("genesis")
+                .ok_or_else(|| format!("{path} has no genesis section"))?;
+            let mut entries = BTreeMap::new();
+            flatten("", genesis, &mut entries);
+            Ok(entries)
+        };
+    let (left, right) = (load(a)?, load(b)?);
+
+    println!("--- {a}");
+    println!("+++ {b}");
+    let mut differences = 0;
+    for (key, value) in &left {
+        match right.get(key) {
+            None => println!("- {key} = {value}"),
+            Some(other) if other != value => {
+                println!("- {key} = {value}");
+                println!("+ {key} = {other}");
+            }
+            Some(_) => continue,
+        }
+        differences += 1;
+    }
+    for (key, value) in right.iter().filter(|(key, _)| !left.contains_key(*key)) {
+        println!("+ {key} = {value}");
+        differences += 1;
+    }
+
+    if differences == 0 {
+        println!("\n✅ Genesis sections are identical");
+    } else {
+        println!("\n⚠️  {differences} genesis entries differ");
+    }
+    Ok(())
+}
+
+/// Flattens `value` into `path → JSON scalar` entries, e.g.
+/// `runtimeGenesis.patch.midnight.networkId`.
+fn flatten(path: &str, value: &Value, entries: &mut BTreeMap<String, String>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{path}.{key}")
+        }
+    };
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                flatten(&join(key), value, entries);
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                flatten(&join(&i.to_string()), value, entries);
+            }
+        }
+        scalar => {
+            entries.insert(path.to_string(), scalar.to_string());
+        }
+    }
+}
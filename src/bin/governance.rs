@@ -0,0 +1,277 @@
+//! # Midnight Governance
+//!
+//! Lists open Council and Technical Committee proposals and federated
+//! authority motions, and makes proposals, votes, approvals and closes,
+//! printing the governance events each produced (see `governance.rs`).
+//!
+//! ## Usage:
+//! ```text
+//! governance motions
+//! governance propose <body> <Pallet.call> [--args <json>] [--threshold <n>]
+//! governance approve <body> <Pallet.call> [--args <json>] [--threshold <n>]
+//! governance revoke  <body> <motion hash> [--threshold <n>]
+//! governance vote    <body> <proposal hash> <aye|nay>
+//! governance close   <body> <proposal hash>
+//! governance close-motion <motion hash>
+//! ```
+//!
+//! `<body>` is `council` or `technical-committee`. `approve` proposes
+//! `FederatedAuthority.motion_approve(call)` in the body, and `revoke`
+//! proposes `motion_revoke`. The threshold defaults to a simple majority of
+//! the body.
+//!
+//! Write commands take the signer options of `signer.rs`: `--suri <uri|dev
+//! name>` (default `alice`) or `--keystore <dir> --key <public key>`,
+//! `--scheme sr25519|ed25519`, `--nonce`, `--mortal <blocks>` and `--tip`.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../governance.rs"]
+mod governance;
+#[path = "../signer.rs"]
+mod signer;
+
+use subxt::utils::H256;
+
+use governance::Body;
+use midnight::api::runtime_types::midnight_node_runtime::RuntimeCall;
+use midnight::api::runtime_types::pallet_federated_authority::pallet::Call as FederatedAuthorityCall;
+use signer::Sender;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Governance ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+
+    if matches!(args.subcommand(), None | Some("motions")) {
+        return list(&api).await;
+    }
+
+    let sender = Sender::from_args(&args)?;
+    let threshold = args.value("threshold").map(str::parse).transpose()?;
+    let body = || -> Result<Body, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(args.positional(1).ok_or("Missing body")?.parse()?)
+    };
+    let hash = |i: usize| -> Result<H256, Box<dyn std::error::Error + Send + Sync>> {
+        let hex_str = args.positional(i).ok_or("Missing hash")?;
+        let bytes = hex::decode(hex_str.trim_start_matches("0x"))?;
+        if bytes.len() != 32 {
+            return Err(format!("Hashes are 32 bytes: {hex_str}").into());
+        }
+        Ok(H256::from_slice(&bytes))
+    };
+
+    let submitted = match args.subcommand() {
+        Some("propose") => {
+            let call = governance::parse_call(
+                &api,
+                args.positional(2).ok_or("Missing call")?,
+                args.value("args"),
+            )?;
+            propose(&api, body()?, call, threshold, &sender).await?
+        }
+        Some("approve") => {
+            let call = governance::parse_call(
+                &api,
+                args.positional(2).ok_or("Missing call")?,
+                args.value("args"),
+            )?;
+            let approve = RuntimeCall::FederatedAuthority(FederatedAuthorityCall::motion_approve {
+                call: Box::new(call),
+            });
+            propose(&api, body()?, approve, threshold, &sender).await?
+        }
+        Some("revoke") => {
+            let revoke = RuntimeCall::FederatedAuthority(FederatedAuthorityCall::motion_revoke {
+                motion_hash: hash(2)?,
+            });
+            propose(&api, body()?, revoke, threshold, &sender).await?
+        }
+        Some("vote") => {
+            let approve = match args.positional(3) {
+                Some("aye") => true,
+                Some("nay") => false,
+                _ => return Err("Vote aye or nay".into()),
+            };
+            vote(&api, body()?, hash(2)?, approve, &sender).await?
+        }
+        Some("close") => close(&api, body()?, hash(2)?, &sender).await?,
+        Some("close-motion") => {
+            let payload = midnight::api::tx()
+                .federated_authority()
+                .motion_close(hash(1)?);
+            sender.submit(&api, &payload).await?
+        }
+        Some(other) => return Err(format!("Unknown subcommand {other}").into()),
+        None => unreachable!("handled above"),
+    };
+
+    if let Some(submitted) = submitted {
+        println!("\n=== Events ===");
+        governance::print_governance_events(&submitted.events);
+    }
+    Ok(())
+}
+
+// ─── Listing ─────────────────────────────────────────────────────────────────
+
+async fn list(api: &chain::Api) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    for body in Body::ALL {
+        let members = governance::members(api, body).await?;
+        let proposals = governance::proposals(api, body).await?;
+        println!(
+            "\n=== {} ({} members, {} proposals) ===",
+            body.pallet(),
+            members.len(),
+            proposals.len()
+        );
+        for proposal in proposals {
+            println!("\n  Proposal 0x{}", hex::encode(proposal.hash.0));
+            if let Some(votes) = &proposal.votes {
+                println!(
+                    "    Index {}, {} ayes / {} nays of {} needed, ends at block {}",
+                    votes.index,
+                    votes.ayes.len(),
+                    votes.nays.len(),
+                    votes.threshold,
+                    votes.end
+                );
+            }
+            match &proposal.call {
+                Some(call) => println!("    Call: {call:#?}"),
+                None => println!("    Call: (gone)"),
+            }
+        }
+    }
+
+    let motions = governance::motions(api).await?;
+    println!("\n=== Federated authority motions ({}) ===", motions.len());
+    for (hash, motion) in motions {
+        println!("\n  Motion 0x{}", hex::encode(hash.0));
+        println!(
+            "    Approved by bodies {:?}, ends at block {}",
+            motion.approvals.0, motion.ends_block
+        );
+        println!("    Call: {:#?}", motion.call);
+    }
+    Ok(())
+}
+
+// ─── Collective calls ────────────────────────────────────────────────────────
+
+async fn propose(
+    api: &chain::Api,
+    body: Body,
+    call: RuntimeCall,
+    threshold: Option<u32>,
+    sender: &Sender,
+) -> Result<Option<chain::Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+    let length_bound = governance::encode_call(api, &call)?.len() as u32;
+    let threshold = match threshold {
+        Some(threshold) => threshold,
+        None => governance::members(api, body).await?.len() as u32 / 2 + 1,
+    };
+    println!("  {} proposal, threshold {threshold}:", body.pallet());
+    println!("  {call:#?}");
+
+    let tx = midnight::api::tx();
+    match body {
+        Body::Council => {
+            let payload = tx.council().propose(threshold, call, length_bound);
+            sender.submit(api, &payload).await
+        }
+        Body::TechnicalCommittee => {
+            let payload = tx
+                .technical_committee()
+                .propose(threshold, call, length_bound);
+            sender.submit(api, &payload).await
+        }
+    }
+}
+
+/// The open proposal `hash` of `body`.
+async fn open_proposal(
+    api: &chain::Api,
+    body: Body,
+    hash: H256,
+) -> Result<governance::Proposal, Box<dyn std::error::Error + Send + Sync>> {
+    governance::proposals(api, body)
+        .await?
+        .into_iter()
+        .find(|proposal| proposal.hash == hash)
+        .ok_or_else(|| {
+            format!(
+                "No open {} proposal 0x{}",
+                body.pallet(),
+                hex::encode(hash.0)
+            )
+            .into()
+        })
+}
+
+async fn vote(
+    api: &chain::Api,
+    body: Body,
+    hash: H256,
+    approve: bool,
+    sender: &Sender,
+) -> Result<Option<chain::Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+    let proposal = open_proposal(api, body, hash).await?;
+    let index = proposal.votes.ok_or("Proposal has no votes record")?.index;
+
+    let tx = midnight::api::tx();
+    match body {
+        Body::Council => {
+            let payload = tx.council().vote(hash, index, approve);
+            sender.submit(api, &payload).await
+        }
+        Body::TechnicalCommittee => {
+            let payload = tx.technical_committee().vote(hash, index, approve);
+            sender.submit(api, &payload).await
+        }
+    }
+}
+
+async fn close(
+    api: &chain::Api,
+    body: Body,
+    hash: H256,
+    sender: &Sender,
+) -> Result<Option<chain::Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+    let proposal = open_proposal(api, body, hash).await?;
+    let index = proposal.votes.ok_or("Proposal has no votes record")?.index;
+    let call = proposal.call.ok_or("Proposal call is gone")?;
+    let length_bound = governance::encode_call(api, &call)?.len() as u32;
+
+    let tx = midnight::api::tx();
+    let constants = midnight::api::constants();
+    match body {
+        Body::Council => {
+            let weight = api
+                .constants()
+                .at(&constants.council().max_proposal_weight())?;
+            let payload = tx.council().close(hash, index, weight, length_bound);
+            sender.submit(api, &payload).await
+        }
+        Body::TechnicalCommittee => {
+            let weight = api
+                .constants()
+                .at(&constants.technical_committee().max_proposal_weight())?;
+            let payload = tx
+                .technical_committee()
+                .close(hash, index, weight, length_bound);
+            sender.submit(api, &payload).await
+        }
+    }
+}
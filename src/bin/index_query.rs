@@ -0,0 +1,295 @@
+//! # Midnight Index Query
+//!
+//! Answers historical lookups from the SQLite index written by `indexer`,
+//! without touching the node.
+//!
+//! ## Usage:
+//! ```text
+//! index_query status
+//! index_query block <number>
+//! index_query tx <tx hash | extrinsic hash>  [--raw]
+//! index_query contract <address>
+//! index_query system [--limit <n>]
+//! index_query events [--pallet <name>] [--variant <name>] [--limit <n>]
+//! ```
+//!
+//! Every subcommand takes `--db <path>` (default `midnight-index.sqlite`).
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../index.rs"]
+mod index;
+
+use rusqlite::{params, OptionalExtension};
+
+use index::{normalize_hex, Index};
+
+const DEFAULT_LIMIT: &str = "20";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let args = cli::Args::from_env(&["raw"]);
+    let index = Index::open(args.value_or("db", index::DEFAULT_DB_PATH))?;
+
+    match args.subcommand() {
+        Some("status") => status(&index),
+        Some("block") => block(
+            &index,
+            args.positional(1).ok_or("Missing block number")?.parse()?,
+        ),
+        Some("tx") => tx(
+            &index,
+            args.positional(1).ok_or("Missing hash")?,
+            args.flag("raw"),
+        ),
+        Some("contract") => contract(&index, args.positional(1).ok_or("Missing address")?),
+        Some("system") => system(&index, args.value_or("limit", DEFAULT_LIMIT).parse()?),
+        Some("events") => events(&index, &args),
+        _ => Err("Usage: index_query <status|block|tx|contract|system|events> ...".into()),
+    }
+}
+
+// ─── Queries ─────────────────────────────────────────────────────────────────
+
+fn status(index: &Index) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let count = |table: &str| -> rusqlite::Result<i64> {
+        index
+            .conn()
+            .query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+                row.get(0)
+            })
+    };
+    match index.last_block()? {
+        Some(last) => println!("  Last block:       {last}"),
+        None => println!("  Last block:       (empty index)"),
+    }
+    println!("  Transactions:     {}", count("transactions")?);
+    println!("  Contract actions: {}", count("contract_actions")?);
+    println!("  Events:           {}", count("events")?);
+    Ok(())
+}
+
+fn block(index: &Index, number: u64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let conn = index.conn();
+    let (hash, parent_hash, timestamp_ms): (String, String, Option<i64>) = conn
+        .query_row(
+            "SELECT hash, parent_hash, timestamp_ms FROM blocks WHERE number = ?1",
+            [number as i64],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )
+        .optional()?
+        .ok_or_else(|| format!("Block {number} is not indexed"))?;
+
+    println!("=== Block {number} ===");
+    println!("  Hash:      0x{hash}");
+    println!("  Parent:    0x{parent_hash}");
+    if let Some(timestamp_ms) = timestamp_ms {
+        println!("  Timestamp: {timestamp_ms}");
+    }
+
+    println!("\n  Transactions:");
+    let mut stmt = conn.prepare(
+        "SELECT extrinsic_index, kind, tx_hash, size FROM transactions
+         WHERE block_number = ?1 ORDER BY rowid",
+    )?;
+    let rows = stmt.query_map([number as i64], |row| {
+        Ok((
+            row.get::<_, u32>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, i64>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (extrinsic_index, kind, tx_hash, size) = row?;
+        println!(
+            "    [{extrinsic_index}] {kind:<8} {} ({size} bytes)",
+            tx_hash.map_or("-".to_string(), |h| format!("0x{h}"))
+        );
+    }
+
+    println!("\n  Events:");
+    let mut stmt = conn.prepare(
+        "SELECT event_index, extrinsic_index, pallet, variant FROM events
+         WHERE block_number = ?1 ORDER BY event_index",
+    )?;
+    let rows = stmt.query_map([number as i64], |row| {
+        Ok((
+            row.get::<_, u32>(0)?,
+            row.get::<_, Option<u32>>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+    for row in rows {
+        let (event_index, extrinsic_index, pallet, variant) = row?;
+        let phase = extrinsic_index.map_or("-".to_string(), |i| i.to_string());
+        println!("    #{event_index:<3} ext {phase:<3} {pallet}.{variant}");
+    }
+    Ok(())
+}
+
+fn tx(
+    index: &Index,
+    hash: &str,
+    raw: bool,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let hash = normalize_hex(hash);
+    let conn = index.conn();
+    let (block_number, extrinsic_index, extrinsic_hash, kind, tx_hash, bytes): (
+        i64,
+        u32,
+        String,
+        String,
+        Option<String>,
+        Vec<u8>,
+    ) = conn
+        .query_row(
+            "SELECT block_number, extrinsic_index, extrinsic_hash, kind, tx_hash, raw
+             FROM transactions WHERE tx_hash = ?1 OR extrinsic_hash = ?1 LIMIT 1",
+            [&hash],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )
+        .optional()?
+        .ok_or_else(|| format!("No indexed transaction with hash 0x{hash}"))?;
+
+    println!("=== Transaction ===");
+    if let Some(tx_hash) = &tx_hash {
+        println!("  Tx hash:   0x{tx_hash}");
+    }
+    println!("  Kind:      {kind}");
+    println!("  Block:     {block_number}");
+    println!("  Extrinsic: {extrinsic_index} (0x{extrinsic_hash})");
+    println!("  Size:      {} bytes", bytes.len());
+
+    if let Some(tx_hash) = &tx_hash {
+        println!("\n  Events:");
+        let mut stmt = conn.prepare(
+            "SELECT pallet, variant, contract_address FROM events
+             WHERE tx_hash = ?1 ORDER BY block_number, event_index",
+        )?;
+        let rows = stmt.query_map([tx_hash], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?;
+        for row in rows {
+            let (pallet, variant, address) = row?;
+            match address {
+                Some(address) => println!("    {pallet}.{variant} {address}"),
+                None => println!("    {pallet}.{variant}"),
+            }
+        }
+    }
+
+    if raw {
+        println!("\n  Raw: {}", hex::encode(&bytes));
+    }
+    Ok(())
+}
+
+fn contract(index: &Index, address: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let address = normalize_hex(address);
+    let mut stmt = index.conn().prepare(
+        "SELECT block_number, action, entry_point, tx_hash FROM contract_actions
+         WHERE contract_address = ?1 ORDER BY block_number, rowid",
+    )?;
+    let rows = stmt.query_map([&address], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+            row.get::<_, String>(3)?,
+        ))
+    })?;
+
+    println!("=== Contract {address} ===");
+    let mut found = 0;
+    for row in rows {
+        let (block_number, action, entry_point, tx_hash) = row?;
+        let action = match entry_point {
+            Some(entry_point) => format!("{action} {entry_point}"),
+            None => action,
+        };
+        println!("  #{block_number:<8} {action:<24} 0x{tx_hash}");
+        found += 1;
+    }
+    if found == 0 {
+        println!("  (no indexed activity)");
+    }
+    Ok(())
+}
+
+fn system(index: &Index, limit: i64) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut stmt = index.conn().prepare(
+        "SELECT block_number, extrinsic_index, size FROM transactions
+         WHERE kind = 'system' ORDER BY block_number DESC, rowid DESC LIMIT ?1",
+    )?;
+    let rows = stmt.query_map([limit], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, u32>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    })?;
+
+    println!("=== System transactions (latest {limit}) ===");
+    for row in rows {
+        let (block_number, extrinsic_index, size) = row?;
+        println!("  #{block_number:<8} ext {extrinsic_index:<3} {size} bytes");
+    }
+    Ok(())
+}
+
+fn events(index: &Index, args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let limit: i64 = args.value_or("limit", DEFAULT_LIMIT).parse()?;
+    let mut stmt = index.conn().prepare(
+        "SELECT block_number, event_index, pallet, variant, tx_hash, contract_address FROM events
+         WHERE (?1 IS NULL OR pallet = ?1) AND (?2 IS NULL OR variant = ?2)
+         ORDER BY block_number DESC, event_index DESC LIMIT ?3",
+    )?;
+    let rows = stmt.query_map(
+        params![args.value("pallet"), args.value("variant"), limit],
+        |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, u32>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        },
+    )?;
+
+    println!("=== Events (latest {limit}) ===");
+    for row in rows {
+        let (block_number, event_index, pallet, variant, tx_hash, address) = row?;
+        let mut line = format!("  #{block_number:<8} {event_index:<3} {pallet}.{variant}");
+        if let Some(tx_hash) = tx_hash {
+            line.push_str(&format!(" tx 0x{tx_hash}"));
+        }
+        if let Some(address) = address {
+            line.push_str(&format!(" contract {address}"));
+        }
+        println!("{line}");
+    }
+    Ok(())
+}
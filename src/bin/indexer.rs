@@ -0,0 +1,106 @@
+//! # Midnight Block Indexer
+//!
+//! Follows the finalized chain and writes every block into a local SQLite
+//! index (see `index.rs`) for `index_query` to read. Restarting picks up
+//! after the last indexed block.
+//!
+//! ## Usage:
+//! ```text
+//! indexer [--db <path>] [--from <block>] [--poll-secs <n>] [--once]
+//! ```
+//!
+//! `--once` stops at the current finalized head instead of following it.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../index.rs"]
+mod index;
+
+use std::time::Duration;
+use subxt::backend::legacy::rpc_methods::NumberOrHex;
+
+use index::Index;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+const DEFAULT_POLL_SECS: u64 = 6;
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Block Indexer ===\n");
+
+    let args = cli::Args::from_env(&["once"]);
+    let db_path = args.value_or("db", index::DEFAULT_DB_PATH);
+    let poll = Duration::from_secs(match args.value("poll-secs") {
+        Some(secs) => secs.parse()?,
+        None => DEFAULT_POLL_SECS,
+    });
+
+    let mut index = Index::open(db_path)?;
+    println!("✓ Index: {db_path}");
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+
+    let mut next = match (index.last_block()?, args.value("from")) {
+        (Some(last), _) => {
+            check_same_chain(&index, &rpc, last).await?;
+            println!("✓ Resuming after block {last}");
+            last + 1
+        }
+        (None, Some(from)) => from.parse()?,
+        (None, None) => 0,
+    };
+
+    loop {
+        let head = api.blocks().at_latest().await?.number() as u64;
+        if next <= head {
+            println!("\nIndexing blocks {next}..={head}");
+        }
+        while next <= head {
+            let block = chain::fetch_block(&api, &rpc, next).await?;
+            index.insert_block(&block)?;
+            if !block.transactions.is_empty() {
+                println!(
+                    "  Block {next}: {} Midnight transactions",
+                    block.transactions.len()
+                );
+            }
+            next += 1;
+        }
+
+        if args.flag("once") {
+            println!("\n✅ Indexed up to block {head}");
+            return Ok(());
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+/// Refuses to extend an index built from another chain.
+async fn check_same_chain(
+    index: &Index,
+    rpc: &chain::Rpc,
+    last: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let indexed = index.block_hash(last)?.unwrap_or_default();
+    let node = rpc
+        .chain_get_block_hash(Some(NumberOrHex::Number(last)))
+        .await?
+        .ok_or_else(|| format!("Block hash missing for block {last}"))?;
+    if indexed != hex::encode(node.0) {
+        return Err(format!(
+            "Block {last} is 0x{indexed} in the index but 0x{} on the node; \
+             the index belongs to another chain",
+            hex::encode(node.0)
+        )
+        .into());
+    }
+    Ok(())
+}
@@ -0,0 +1,304 @@
+//! # Midnight Transaction Inspector
+//!
+//! Decodes a serialized `FinalizedTransaction` or `SystemTransaction` and
+//! prints its structure (system transactions are classified by `systx.rs`).
+//! When the node is reachable the result is cross-checked against its
+//! `get_decoded_transaction` runtime API.
+//!
+//! ## Usage:
+//! ```text
+//! inspect <hex>
+//! inspect --file <path>                  (hex text or raw bytes)
+//! inspect --block <n> [--extrinsic <i>]  (every Midnight tx in the block)
+//!         [--offline]
+//! ```
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../systx.rs"]
+mod systx;
+
+use midnight_node_ledger_helpers::*;
+
+use chain::RawTransaction;
+use midnight::api::runtime_types::midnight_node_ledger::common::types::{
+    Op as DecodedOp, Tx as DecodedTx,
+};
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Transaction Inspector ===\n");
+
+    let args = cli::Args::from_env(&["offline"]);
+
+    let api = if args.flag("offline") {
+        None
+    } else {
+        match chain::connect(NODE_URL).await {
+            Ok(connection) => Some(connection),
+            Err(e) if args.value("block").is_none() => {
+                println!("⚠️ Node not reachable ({e}); skipping cross-check");
+                None
+            }
+            Err(e) => return Err(e),
+        }
+    };
+
+    let transactions = if let Some(block) = args.value("block") {
+        let (api, rpc) = api.as_ref().ok_or("--block needs the node")?;
+        let block = chain::fetch_block(api, rpc, block.parse()?).await?;
+        let extrinsic = args.value("extrinsic").map(str::parse::<u32>).transpose()?;
+        block
+            .transactions
+            .into_iter()
+            .filter(|tx| extrinsic.is_none_or(|i| tx.extrinsic_index == i))
+            .map(|tx| {
+                let origin = systx::Origin::of(block.number, &tx);
+                (
+                    format!("extrinsic {}", tx.extrinsic_index),
+                    tx.raw,
+                    Some(origin),
+                )
+            })
+            .collect()
+    } else {
+        let bytes = match (args.value("file"), args.positional(0)) {
+            (Some(path), _) => read_file(path)?,
+            (None, Some(hex_str)) => hex::decode(hex_str.trim().trim_start_matches("0x"))?,
+            (None, None) => {
+                return Err("Usage: inspect <hex> | --file <path> | --block <n>".into());
+            }
+        };
+        vec![("input".to_string(), classify(bytes)?, None)]
+    };
+
+    if transactions.is_empty() {
+        println!("  (no Midnight transactions)");
+    }
+    for (label, raw, origin) in transactions {
+        println!("\n=== Transaction ({label}) ===");
+        match raw {
+            RawTransaction::Standard(bytes) => {
+                let tx = deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice())?;
+                print_transaction(&tx);
+                if let Some((api, _rpc)) = &api {
+                    cross_check(api, &tx, bytes).await?;
+                }
+            }
+            RawTransaction::System(bytes) => {
+                let tx = deserialize::<SystemTransaction, _>(&mut bytes.as_slice())?;
+                println!("  System transaction");
+                systx::print(&systx::classify(&tx, origin));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a file holding either hex text or the raw serialized bytes.
+fn read_file(path: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = std::fs::read(path)?;
+    match std::str::from_utf8(&bytes) {
+        Ok(text) => match hex::decode(text.trim().trim_start_matches("0x")) {
+            Ok(decoded) => Ok(decoded),
+            Err(_) => Ok(bytes),
+        },
+        Err(_) => Ok(bytes),
+    }
+}
+
+/// Works out whether `bytes` hold a standard or a system transaction.
+fn classify(bytes: Vec<u8>) -> Result<RawTransaction, Box<dyn std::error::Error + Send + Sync>> {
+    if deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice()).is_ok() {
+        return Ok(RawTransaction::Standard(bytes));
+    }
+    if deserialize::<SystemTransaction, _>(&mut bytes.as_slice()).is_ok() {
+        return Ok(RawTransaction::System(bytes));
+    }
+    Err("Input is neither a FinalizedTransaction nor a SystemTransaction".into())
+}
+
+// ─── Printing ────────────────────────────────────────────────────────────────
+
+fn print_transaction(tx: &FinalizedTransaction<DefaultDB>) {
+    println!("  Hash:        {}", hex::encode(tx.transaction_hash().0 .0));
+    println!("  Identifiers: {}", tx.identifiers().count());
+
+    let stx = match tx {
+        Transaction::Standard(stx) => stx,
+        Transaction::ClaimRewards(claim) => {
+            println!("  Kind:        claim rewards");
+            println!("  Network ID:  {}", claim.network_id);
+            println!("  Value:       {}", claim.value);
+            println!("  Reward kind: {:?}", claim.kind);
+            return;
+        }
+    };
+
+    println!("  Kind:        standard");
+    println!("  Network ID:  {}", stx.network_id);
+    println!("  Binding:     {:?}", stx.binding_randomness);
+
+    match &stx.guaranteed_coins {
+        Some(offer) => print_offer("Guaranteed coins", offer),
+        None => println!("  Guaranteed coins: none"),
+    }
+    for (segment, offer) in stx.fallible_coins.iter() {
+        print_offer(&format!("Fallible coins (segment {segment})"), &offer);
+    }
+
+    println!("  Intents: {}", stx.intents.len());
+    for (segment, intent) in stx.intents.iter() {
+        println!("\n  Intent (segment {segment})");
+        println!("    TTL: {:?}", intent.ttl);
+        for (label, offer) in [
+            ("Guaranteed unshielded", &intent.guaranteed_unshielded_offer),
+            ("Fallible unshielded", &intent.fallible_unshielded_offer),
+        ] {
+            if let Some(offer) = offer {
+                println!(
+                    "    {label}: {} inputs, {} outputs, {} signatures",
+                    offer.inputs.len(),
+                    offer.outputs.len(),
+                    offer.signatures.len()
+                );
+            }
+        }
+        if let Some(dust) = &intent.dust_actions {
+            println!(
+                "    DUST: {} spends, {} registrations",
+                dust.spends.len(),
+                dust.registrations.len()
+            );
+        }
+        println!("    Contract actions: {}", intent.actions.len());
+        for action in intent.actions.iter() {
+            print_action(&action);
+        }
+    }
+}
+
+fn print_offer(label: &str, offer: &ZswapOffer<Proof, DefaultDB>) {
+    println!(
+        "  {label}: {} inputs, {} outputs, {} transients",
+        offer.inputs.len(),
+        offer.outputs.len(),
+        offer.transients.len()
+    );
+    for (token_type, value) in offer.deltas.iter() {
+        println!("    Delta {}: {value}", hex::encode(token_type.0 .0));
+    }
+}
+
+fn print_action(action: &ContractAction<ProofMarker, DefaultDB>) {
+    match action {
+        ContractAction::Call(call) => println!(
+            "      Call {} → {}",
+            hex::encode(call.address.0 .0),
+            String::from_utf8_lossy(&call.entry_point.0)
+        ),
+        ContractAction::Deploy(deploy) => {
+            println!("      Deploy {}", hex::encode(deploy.address().0 .0))
+        }
+        ContractAction::Maintain(update) => println!(
+            "      Maintain {} (counter {}, {} updates, {} signatures)",
+            hex::encode(update.address.0 .0),
+            update.counter,
+            update.updates.len(),
+            update.signatures.len()
+        ),
+    }
+}
+
+// ─── Cross-check ─────────────────────────────────────────────────────────────
+
+/// Compares our decoding with the node's `get_decoded_transaction` and shows
+/// the node's fee estimate.
+async fn cross_check(
+    api: &chain::Api,
+    tx: &FinalizedTransaction<DefaultDB>,
+    bytes: Vec<u8>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let runtime_api = api.runtime_api().at_latest().await?;
+    let apis = midnight::api::apis().midnight_runtime_api();
+
+    let decoded: DecodedTx = match runtime_api
+        .call(apis.get_decoded_transaction(bytes.clone()))
+        .await?
+    {
+        Ok(decoded) => decoded,
+        Err(e) => {
+            println!("\n  ⚠️ Node could not decode the transaction: {e:?}");
+            return Ok(());
+        }
+    };
+
+    println!("\n  Node cross-check:");
+    let mut mismatches = 0;
+    let mut check = |what: &str, ours: String, node: String| {
+        if ours == node {
+            println!("    ✓ {what}: {ours}");
+        } else {
+            println!("    ⚠️ {what}: ours {ours}, node {node}");
+            mismatches += 1;
+        }
+    };
+
+    check(
+        "hash",
+        hex::encode(tx.transaction_hash().0 .0),
+        hex::encode(decoded.hash),
+    );
+    check(
+        "identifiers",
+        tx.identifiers().count().to_string(),
+        decoded.identifiers.len().to_string(),
+    );
+    if let Transaction::Standard(stx) = tx {
+        let actions: usize = stx
+            .intents
+            .iter()
+            .map(|(_, intent)| intent.actions.len())
+            .sum();
+        check(
+            "contract actions",
+            actions.to_string(),
+            decoded
+                .operations
+                .iter()
+                .filter(|op| !matches!(op, DecodedOp::ClaimRewards { .. }))
+                .count()
+                .to_string(),
+        );
+        check(
+            "guaranteed coins",
+            stx.guaranteed_coins.is_some().to_string(),
+            decoded.has_guaranteed_coins.to_string(),
+        );
+        check(
+            "fallible coins",
+            (!stx.fallible_coins.is_empty()).to_string(),
+            decoded.has_fallible_coins.to_string(),
+        );
+    }
+
+    match runtime_api.call(apis.get_transaction_cost(bytes)).await? {
+        Ok(cost) => println!("    Fee (node estimate): {cost}"),
+        Err(e) => println!("    ⚠️ Node could not cost the transaction: {e:?}"),
+    }
+
+    if mismatches > 0 {
+        return Err(format!("{mismatches} fields differ from the node's decoding").into());
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! # Midnight Key Cache
+//!
+//! Manages the proving key and parameter cache described in `keys.rs`.
+//!
+//! ## Usage:
+//! ```text
+//! key_cache prefetch [--max-k <k>] [--contract <name>=<dir>]...
+//! key_cache verify
+//! key_cache path
+//! ```
+//!
+//! `prefetch` downloads public params and DUST keys and imports contract keys
+//! (default `bboard=static/bboard`), after which proving needs no network.
+//! Contract keys are checked against the `SHA256SUMS` their directory ships
+//! with, if any.
+//! `verify` checks cached contract keys; params and DUST keys are checked by
+//! the ledger's data provider whenever they are loaded.
+//! Set `MIDNIGHT_KEY_CACHE` to use a cache other than `.midnight-cache`.
+
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../keys.rs"]
+mod keys;
+
+use std::path::Path;
+
+use keys::KeyCache;
+
+/// Largest params degree fetched by default; enough for BBoard and DUST.
+const DEFAULT_MAX_K: u8 = 15;
+
+const DEFAULT_CONTRACT: &str = "bboard=static/bboard";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Key Cache ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let cache = KeyCache::from_env();
+
+    match args.subcommand() {
+        Some("prefetch") => prefetch(&args, &cache).await,
+        Some("verify") => verify(&cache),
+        Some("path") => {
+            println!("{}", cache.dir().display());
+            Ok(())
+        }
+        _ => Err("Usage: key_cache <prefetch|verify|path>".into()),
+    }
+}
+
+// ─── Subcommands ─────────────────────────────────────────────────────────────
+
+async fn prefetch(
+    args: &cli::Args,
+    cache: &KeyCache,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let max_k: u8 = match args.value("max-k") {
+        Some(k) => k.parse()?,
+        None => DEFAULT_MAX_K,
+    };
+
+    println!(
+        "Fetching params and DUST keys into {}",
+        cache.params_dir().display()
+    );
+    cache.prefetch_params(max_k).await?;
+
+    let mut contracts = args.values("contract");
+    if contracts.is_empty() {
+        contracts.push(DEFAULT_CONTRACT);
+    }
+    for contract in contracts {
+        let (name, dir) = contract
+            .split_once('=')
+            .ok_or_else(|| format!("Expected --contract <name>=<dir>, got {contract}"))?;
+        let count = cache.import_contract(name, Path::new(dir))?;
+        println!("  ✓ {name}: {count} files from {dir}");
+    }
+
+    println!("\n✅ Cache ready at {}", cache.dir().display());
+    Ok(())
+}
+
+fn verify(cache: &KeyCache) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let contracts = cache.cached_contracts();
+    if contracts.is_empty() {
+        println!("  (no contracts cached; run `key_cache prefetch`)");
+    }
+
+    let mut failed = 0;
+    for name in contracts {
+        let mismatched = cache.verify_contract(&name)?;
+        if mismatched.is_empty() {
+            println!("  ✓ {name}");
+        } else {
+            for file in &mismatched {
+                println!("  ⚠️ {name}/{file}: checksum mismatch");
+            }
+            failed += mismatched.len();
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{failed} cached files failed verification").into());
+    }
+    println!("\n✅ Cache verified");
+    Ok(())
+}
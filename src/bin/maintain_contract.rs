@@ -0,0 +1,295 @@
+//! # Midnight Contract Maintenance
+//!
+//! Uses a contract's `ContractMaintenanceAuthority` to update it after deploy.
+//!
+//! ## Flow:
+//! 1. `prepare` an unsigned update against the contract's current counter
+//! 2. Every committee member runs `sign` on the pending file with their seed
+//! 3. Once the threshold is reached, `submit` proves and sends it
+//!
+//! ## Usage:
+//! ```text
+//! maintain_contract show    <name|address>
+//! maintain_contract prepare <name|address> --out <file>
+//!                           [--replace-vk <op>=<verifier file>]...
+//!                           [--add-op <op>=<verifier file>]...
+//!                           [--remove-op <op>]...
+//!                           [--committee-seed <hex>]... [--committee-key <hex>]...
+//!                           [--committee-file <path>] [--threshold <n>]
+//! maintain_contract sign    <file> --seed <hex> [--rng-seed <seed>]
+//! maintain_contract submit  <file> [--seed <hex>]
+//!                           [--proof-server <url> | --mock-proofs | --build-only <file>]
+//!                           [--rng-seed <seed>] [--verify-root-every <n>]
+//!                           [--bootstrap-from <block> [--bootstrap-contract <address>]]
+//! ```
+//!
+//! Contracts can be referenced by their name in the network's deployment
+//! registry (see `deploy_contract`) or by hex address.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../bboard.rs"]
+mod bboard;
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../committee.rs"]
+mod committee;
+#[path = "../keys.rs"]
+mod keys;
+#[path = "../maintenance.rs"]
+mod maintenance;
+#[path = "../manifest.rs"]
+mod manifest;
+#[path = "../prover.rs"]
+mod prover;
+#[path = "../rng.rs"]
+mod rng;
+#[path = "../txfile.rs"]
+mod txfile;
+#[path = "../witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::contract::BuildContractAction;
+use midnight_node_ledger_helpers::wallet::UnshieldedWallet;
+use midnight_node_ledger_helpers::*;
+
+use std::sync::Arc;
+
+use committee::CommitteeConfig;
+use maintenance::{MaintenanceOp, MaintenanceUpdateAction};
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// Wallet seed (hex-encoded, 32 bytes). Pays the fees on submit.
+const WALLET_SEED_HEX: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    keys::KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Contract Maintenance ===\n");
+
+    let args = cli::Args::from_env(prover::SWITCHES);
+    match args.subcommand() {
+        Some("show") => show(&args).await,
+        Some("prepare") => prepare(&args).await,
+        Some("sign") => sign(&args).await,
+        Some("submit") => submit(&args).await,
+        _ => Err("Usage: maintain_contract <show|prepare|sign|submit> ...".into()),
+    }
+}
+
+// ─── Subcommands ─────────────────────────────────────────────────────────────
+
+async fn show(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let contract = args.positional(1).ok_or("Missing <name|address>")?;
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let address = manifest::resolve_contract(&api, contract).await?;
+    let state = chain::fetch_contract_state(&api, &address).await?;
+
+    let authority = &state.maintenance_authority;
+    println!("\n=== Maintenance Authority ===");
+    println!(
+        "  Threshold: {}/{}",
+        authority.threshold,
+        authority.committee.len()
+    );
+    println!("  Counter:   {}", authority.counter);
+    for (i, member) in authority.committee.iter().enumerate() {
+        println!("  Member {i}: {}", hex::encode(serialize(member)?));
+    }
+
+    println!("\n=== Operations ===");
+    for (name, _op) in state.operations.iter() {
+        println!("  {}", String::from_utf8_lossy(&name.0));
+    }
+
+    Ok(())
+}
+
+async fn prepare(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let contract = args.positional(1).ok_or("Missing <name|address>")?;
+    let out = args.require("out")?;
+
+    let mut ops = Vec::new();
+    for spec in args.values("replace-vk") {
+        let (operation, path) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("Expected <op>=<verifier file>, got '{spec}'"))?;
+        ops.push(MaintenanceOp::ReplaceVerifierKey {
+            operation: operation.to_string(),
+            key: bboard::load_verifier_key(path)?,
+        });
+    }
+    for spec in args.values("add-op") {
+        let (operation, path) = spec
+            .split_once('=')
+            .ok_or_else(|| format!("Expected <op>=<verifier file>, got '{spec}'"))?;
+        ops.push(MaintenanceOp::AddOperation {
+            operation: operation.to_string(),
+            key: bboard::load_verifier_key(path)?,
+        });
+    }
+    for operation in args.values("remove-op") {
+        ops.push(MaintenanceOp::RemoveOperation {
+            operation: operation.to_string(),
+        });
+    }
+    let members = committee::members_from_args(args)?;
+    if !members.is_empty() {
+        let rotation = CommitteeConfig {
+            members,
+            threshold: args.require("threshold")?.parse()?,
+        };
+        rotation.validate()?;
+        ops.push(MaintenanceOp::RotateCommittee {
+            committee: rotation.members,
+            threshold: rotation.threshold,
+        });
+    }
+    if ops.is_empty() {
+        return Err(
+            "Nothing to do: pass --replace-vk, --add-op, --remove-op or committee members".into(),
+        );
+    }
+
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let address = manifest::resolve_contract(&api, contract).await?;
+    let state = chain::fetch_contract_state(&api, &address).await?;
+    for op in &ops {
+        maintenance::validate_op(op, &state)?;
+    }
+
+    let update = maintenance::prepare_update(address, &ops, &state)?;
+    maintenance::write_update_file(out, &update)?;
+
+    println!(
+        "✓ Update prepared (counter {})",
+        state.maintenance_authority.counter
+    );
+    println!(
+        "  Needs {} signature(s); pass {out} to each committee member",
+        state.maintenance_authority.threshold
+    );
+
+    Ok(())
+}
+
+async fn sign(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = args.positional(1).ok_or("Missing <file>")?;
+    let seed: WalletSeed = args
+        .require("seed")?
+        .parse()
+        .map_err(|e| format!("Invalid --seed: {e:?}"))?;
+
+    let update = maintenance::read_update_file(path)?;
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let state = chain::fetch_contract_state(&api, &update.address).await?;
+
+    maintenance::check_counter(&update, &state)?;
+
+    let signing_key = UnshieldedWallet::default(seed).signing_key().clone();
+    let mut rng = rng::rng(rng::seed_from_args(args), "maintenance-signature");
+    let update =
+        maintenance::sign_update(update, &signing_key, &state.maintenance_authority, &mut rng)?;
+    maintenance::write_update_file(path, &update)?;
+
+    println!(
+        "✓ Signed ({}/{} signatures)",
+        maintenance::signature_count(&update),
+        state.maintenance_authority.threshold
+    );
+
+    Ok(())
+}
+
+async fn submit(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = args.positional(1).ok_or("Missing <file>")?;
+    let seed: WalletSeed = args
+        .value_or("seed", WALLET_SEED_HEX)
+        .parse()
+        .map_err(|e| format!("Invalid --seed: {e:?}"))?;
+
+    let update = maintenance::read_update_file(path)?;
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+    let state = chain::fetch_contract_state(&api, &update.address).await?;
+    maintenance::check_counter(&update, &state)?;
+    let signatures = maintenance::signature_count(&update);
+    if signatures < state.maintenance_authority.threshold as usize {
+        return Err(format!(
+            "Only {signatures} of {} required signatures collected",
+            state.maintenance_authority.threshold
+        )
+        .into());
+    }
+
+    let network_id = chain::fetch_network_id(&api).await?;
+    println!("✓ Network ID: {network_id}");
+    chain::ensure_ledger_compatible(&api).await?;
+
+    let context = Arc::new(LedgerContext::<DefaultDB>::new_from_wallet_seeds(
+        &network_id,
+        &[seed],
+    ));
+    chain::sync(&api, &rpc, &context, &chain::SyncOptions::from_args(args)?).await?;
+
+    // ── Build the maintenance intent ─────────────────────────────────────
+    let actions: Vec<Box<dyn BuildContractAction<DefaultDB>>> =
+        vec![Box::new(MaintenanceUpdateAction {
+            update: update.clone(),
+        })];
+
+    let intent_info = IntentInfo {
+        guaranteed_unshielded_offer: None,
+        fallible_unshielded_offer: None,
+        actions,
+    };
+
+    let prover = prover::from_args(args, &network_id).await?;
+    let mut tx_info = StandardTrasactionInfo::new_from_context(
+        context.clone(),
+        prover.provider.clone(),
+        rng::seed_from_args(args),
+    );
+    tx_info.add_intent(1, Box::new(intent_info));
+    tx_info.set_guaranteed_offer(OfferInfo {
+        inputs: vec![],
+        outputs: vec![],
+        transients: vec![],
+    });
+    tx_info.set_funding_seeds(vec![seed]);
+    tx_info.use_mock_proofs_for_fees(true);
+
+    println!("\nProving transaction...");
+    let proven_tx = match prover.prove(&mut tx_info).await? {
+        prover::Outcome::Proven(tx) => tx,
+        // Continued by `offline_tx prove` and `offline_tx submit`
+        prover::Outcome::Exported(_) => return Ok(()),
+    };
+    println!("✓ Transaction proven");
+
+    let serialized = serialize(&proven_tx)?;
+    println!("✓ Serialized ({} bytes)", serialized.len());
+
+    let Some(submitted) = chain::submit_transaction(&api, serialized).await? else {
+        return Err("Maintenance update failed on chain".into());
+    };
+    if maintenance::print_maintain_events(&submitted.events, &update) == 0 {
+        return Err(format!(
+            "No ContractMaintain event for {}",
+            hex::encode(update.address.0 .0)
+        )
+        .into());
+    }
+
+    Ok(())
+}
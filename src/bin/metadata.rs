@@ -0,0 +1,212 @@
+//! # Midnight Metadata Drift
+//!
+//! `src/midnight.rs` is generated by `subxt codegen` from one runtime's
+//! metadata. This compares it with the node's live metadata, reports which
+//! pallets, calls and runtime APIs changed, and regenerates the bindings.
+//!
+//! ## Usage:
+//! ```text
+//! metadata check     (exit code 1 when the bindings are stale)
+//! metadata refresh   (needs `cargo install subxt-cli@0.44`)
+//! ```
+//!
+//! `refresh` also saves the metadata it generated from to
+//! `static/metadata.scale`; `check` uses that snapshot to name changed calls.
+//! Without it only added and removed pallets can be reported; running
+//! `refresh` against a node on the runtime the bindings came from writes the
+//! snapshot and leaves the bindings unchanged.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use subxt::ext::codec::Decode;
+use subxt::Metadata;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+/// Metadata `src/midnight.rs` was last generated from.
+const METADATA_SNAPSHOT: &str = "static/metadata.scale";
+const BINDINGS: &str = "src/midnight.rs";
+/// Metadata version requested from the node.
+const METADATA_VERSION: u32 = 15;
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Metadata Drift ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+
+    match args.subcommand() {
+        None | Some("check") => {
+            let live = api.metadata();
+            if midnight::api::is_codegen_valid_for(&live) {
+                println!("✅ {BINDINGS} matches the node's metadata");
+                return Ok(());
+            }
+            println!("⚠️  {BINDINGS} does not match the node's metadata\n");
+            match std::fs::read(METADATA_SNAPSHOT) {
+                Ok(bytes) => report_drift(&Metadata::decode(&mut bytes.as_slice())?, &live),
+                Err(_) => report_pallets(&live),
+            }
+            Err(format!("{BINDINGS} is stale; run `metadata refresh`").into())
+        }
+        Some("refresh") => refresh(&api).await,
+        Some(other) => Err(format!("Unknown subcommand {other}; expected check or refresh").into()),
+    }
+}
+
+// ─── Drift report ────────────────────────────────────────────────────────────
+
+/// Pallets the bindings know about versus the node's, by name only.
+fn report_pallets(live: &Metadata) {
+    println!("  (no {METADATA_SNAPSHOT}; comparing pallet names only)");
+    let generated: BTreeSet<&str> = midnight::api::PALLETS.iter().copied().collect();
+    let live_pallets: BTreeSet<&str> = live.pallets().map(|p| p.name()).collect();
+    for name in generated.difference(&live_pallets) {
+        println!("  - pallet {name} (removed)");
+    }
+    for name in live_pallets.difference(&generated) {
+        println!("  + pallet {name} (new)");
+    }
+    println!("\n  Pallets present in both may still have changed calls, events or storage");
+}
+
+/// Pallet, call and runtime API differences between the snapshot the
+/// bindings were generated from and the node's metadata.
+fn report_drift(snapshot: &Metadata, live: &Metadata) {
+    let mut changes = 0;
+
+    for pallet in snapshot.pallets() {
+        let Some(live_pallet) = live.pallet_by_name(pallet.name()) else {
+            println!("  - pallet {} (removed)", pallet.name());
+            changes += 1;
+            continue;
+        };
+        if pallet.hash() == live_pallet.hash() {
+            continue;
+        }
+        println!("  ~ pallet {}", pallet.name());
+        changes += 1;
+
+        let calls = |p: &subxt::metadata::types::PalletMetadata| -> BTreeSet<String> {
+            p.call_variants()
+                .map(|variants| variants.iter().map(|v| v.name.clone()).collect())
+                .unwrap_or_default()
+        };
+        let (old_calls, new_calls) = (calls(&pallet), calls(&live_pallet));
+        for call in old_calls.difference(&new_calls) {
+            println!("      - call {call}");
+        }
+        for call in new_calls.difference(&old_calls) {
+            println!("      + call {call}");
+        }
+        for call in old_calls.intersection(&new_calls) {
+            if pallet.call_hash(call) != live_pallet.call_hash(call) {
+                println!("      ~ call {call}");
+            }
+        }
+    }
+    for pallet in live.pallets() {
+        if snapshot.pallet_by_name(pallet.name()).is_none() {
+            println!("  + pallet {} (new)", pallet.name());
+            changes += 1;
+        }
+    }
+
+    for api in snapshot.runtime_api_traits() {
+        match live.runtime_api_trait_by_name(api.name()) {
+            None => println!("  - runtime API {} (removed)", api.name()),
+            Some(live_api) if live_api.hash() != api.hash() => {
+                println!("  ~ runtime API {}", api.name())
+            }
+            Some(_) => continue,
+        }
+        changes += 1;
+    }
+    for api in live.runtime_api_traits() {
+        if snapshot.runtime_api_trait_by_name(api.name()).is_none() {
+            println!("  + runtime API {} (new)", api.name());
+            changes += 1;
+        }
+    }
+
+    if changes == 0 {
+        println!("  No pallet or runtime API changes against {METADATA_SNAPSHOT}; it is");
+        println!("  probably older than {BINDINGS} (rerun `metadata refresh`)");
+    }
+}
+
+// ─── Refresh ─────────────────────────────────────────────────────────────────
+
+/// Regenerates the bindings from the node's metadata with `subxt codegen`,
+/// formatted with rustfmt, then saves that metadata as the snapshot. The
+/// snapshot is only replaced once the bindings are, so the two never drift
+/// apart when codegen or rustfmt fails.
+async fn refresh(api: &chain::Api) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let metadata = api
+        .runtime_api()
+        .at_latest()
+        .await?
+        .call(
+            midnight::api::apis()
+                .metadata()
+                .metadata_at_version(METADATA_VERSION),
+        )
+        .await?
+        .ok_or_else(|| format!("The node does not serve metadata V{METADATA_VERSION}"))?
+        .0;
+    let fetched = std::env::temp_dir().join(format!("metadata-{}.scale", std::process::id()));
+    std::fs::write(&fetched, &metadata)?;
+    let codegen = Command::new("subxt")
+        .arg("codegen")
+        .arg("--file")
+        .arg(&fetched)
+        .output();
+    let _ = std::fs::remove_file(&fetched);
+    let codegen = codegen.map_err(|e| {
+        format!("Could not run subxt ({e}); install it with `cargo install subxt-cli@0.44`")
+    })?;
+    if !codegen.status.success() {
+        return Err(format!(
+            "subxt codegen failed: {}",
+            String::from_utf8_lossy(&codegen.stderr)
+        )
+        .into());
+    }
+
+    let mut rustfmt = Command::new("rustfmt")
+        .args(["--edition", "2021", "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+    rustfmt
+        .stdin
+        .take()
+        .ok_or("rustfmt stdin unavailable")?
+        .write_all(&codegen.stdout)?;
+    let formatted = rustfmt.wait_with_output()?;
+    if !formatted.status.success() {
+        return Err("rustfmt failed on the generated bindings".into());
+    }
+
+    std::fs::write(BINDINGS, &formatted.stdout)?;
+    println!("✓ Regenerated {BINDINGS}");
+    std::fs::write(METADATA_SNAPSHOT, &metadata)?;
+    println!(
+        "✓ Saved {} bytes of metadata to {METADATA_SNAPSHOT}",
+        metadata.len()
+    );
+    println!("\n  Rebuild, then fix any code the changed types break");
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! # Midnight Offline Transactions
+//!
+//! The proving and submitting stages of the offline workflow (see
+//! `txfile.rs`); the building stage is `--build-only <file>` on the binaries
+//! that build transactions.
+//!
+//! ## Usage:
+//! ```text
+//! offline_tx prove  <unproven file> --out <proven file>
+//!                   [--contract <name>] [--contract-dir <dir>]
+//!                   [--proof-server <url> | --mock-proofs]
+//! offline_tx submit <proven file>
+//! offline_tx show   <file>
+//! ```
+//!
+//! `prove` needs no node, only the key cache (see `key_cache`). Circuit keys
+//! are those of contract `--contract` (default `bboard`), read from the cache
+//! or, for a contract never imported, from `--contract-dir`.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../bboard.rs"]
+mod bboard;
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../keys.rs"]
+mod keys;
+#[path = "../prover.rs"]
+mod prover;
+#[path = "../txfile.rs"]
+mod txfile;
+#[path = "../witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::*;
+
+use rand::SeedableRng;
+
+use txfile::{Stage, TxFile};
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    keys::KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Offline Transactions ===\n");
+
+    let args = cli::Args::from_env(prover::SWITCHES);
+    match args.subcommand() {
+        Some("prove") => prove(&args).await,
+        Some("submit") => submit(&args).await,
+        Some("show") => show(&args),
+        _ => Err("Usage: offline_tx <prove|submit|show> <file> ...".into()),
+    }
+}
+
+// ─── Subcommands ─────────────────────────────────────────────────────────────
+
+async fn prove(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = args.positional(1).ok_or("Missing <unproven file>")?;
+    let out = args.require("out")?;
+
+    let file = TxFile::read(path, Stage::Unproven)?;
+    let tx = file.unproven_tx()?;
+    let cost_model = file.cost_model()?;
+    println!(
+        "✓ Loaded unproven transaction for network {}",
+        file.network_id
+    );
+
+    let cache = keys::KeyCache::from_env();
+    let contract = args.value_or("contract", "bboard");
+    let source_dir = match args.value("contract-dir") {
+        Some(dir) => dir,
+        None if contract == "bboard" => bboard::BBOARD_DIR,
+        None if cache.cached_contracts().iter().any(|name| name == contract) => "",
+        None => {
+            return Err(format!(
+                "Contract {contract} is not in the key cache; import it with \
+                 `key_cache prefetch --contract {contract}=<dir>` or pass --contract-dir"
+            )
+            .into())
+        }
+    };
+    let resolver = cache.contract_resolver(contract, source_dir)?;
+    println!("✓ Using circuit keys of contract {contract}");
+
+    let prover = prover::from_args(args, &file.network_id).await?;
+
+    println!("\nProving transaction...");
+    let proven = prover
+        .provider
+        .prove(tx, StdRng::from_entropy(), &resolver, &cost_model)
+        .await
+        .map_err(|e| e.to_string())?;
+    println!("✓ Transaction proven");
+
+    TxFile::proven(&file.network_id, &proven)?.write(out)?;
+    println!("✓ Proven transaction written to {out}");
+    Ok(())
+}
+
+async fn submit(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = args.positional(1).ok_or("Missing <proven file>")?;
+    let file = TxFile::read(path, Stage::Proven)?;
+
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let network_id = chain::fetch_network_id(&api).await?;
+    file.check_network(&network_id)?;
+    println!("✓ Network ID: {network_id}");
+    chain::ensure_ledger_compatible(&api).await?;
+
+    let serialized = file.tx_bytes()?;
+    println!("✓ Loaded proven transaction ({} bytes)", serialized.len());
+
+    chain::submit_transaction(&api, serialized).await?;
+    Ok(())
+}
+
+fn show(args: &cli::Args) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let path = args.positional(1).ok_or("Missing <file>")?;
+    let contents = std::fs::read_to_string(path)?;
+    let file: TxFile = serde_json::from_str(&contents)?;
+
+    println!("  Stage:      {:?}", file.stage);
+    println!("  Version:    {}", file.version);
+    println!("  Network ID: {}", file.network_id);
+    println!("  Size:       {} bytes", file.tx_bytes()?.len());
+    if file.version != txfile::TX_FILE_VERSION {
+        println!("  ⚠️ This build reads version {}", txfile::TX_FILE_VERSION);
+    }
+    Ok(())
+}
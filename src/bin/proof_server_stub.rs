@@ -0,0 +1,163 @@
+//! # Local Proof Server Stand-in
+//!
+//! Serves the protocol `RemoteProofServer` speaks (see `prover.rs`) and
+//! proves with `LocalProofServer`, so the remote proving path can be tested
+//! without deploying a real proof server. With `--mock-proofs` it answers
+//! with mock proofs instead, for clients that cannot mock-prove themselves.
+//!
+//! Bodies over `MAX_BODY` get `413`, undecodable ones `400` and proving
+//! failures `500`, which `RemoteProofServer` retries.
+//!
+//! ## Usage:
+//! ```text
+//! proof_server_stub [--listen 127.0.0.1:6300] [--mock-proofs]
+//! ```
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../bboard.rs"]
+mod bboard;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../keys.rs"]
+mod keys;
+#[path = "../prover.rs"]
+mod prover;
+#[path = "../txfile.rs"]
+mod txfile;
+#[path = "../witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::*;
+
+use rand::SeedableRng;
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const DEFAULT_LISTEN: &str = "127.0.0.1:6300";
+
+/// Largest request body accepted, well above any real unproven transaction
+/// and cost model.
+const MAX_BODY: usize = 64 * 1024 * 1024;
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    keys::KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Proof Server Stand-in ===\n");
+
+    let args = cli::Args::from_env(prover::SWITCHES);
+    let listen = args.value_or("listen", DEFAULT_LISTEN);
+
+    let listener = TcpListener::bind(listen).await?;
+    let prover: Arc<dyn ProofProvider<DefaultDB>> = if args.flag("mock-proofs") {
+        println!("⚠️ Serving mock proofs");
+        Arc::new(prover::MockProofServer)
+    } else {
+        Arc::new(LocalProofServer::new())
+    };
+    println!("✓ Listening on http://{listen}");
+
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        let prover = prover.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle(stream, prover).await {
+                eprintln!("  ⚠ {peer}: {e}");
+            }
+        });
+    }
+}
+
+// ─── HTTP handling ───────────────────────────────────────────────────────────
+
+async fn handle(
+    stream: TcpStream,
+    prover: Arc<dyn ProofProvider<DefaultDB>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).await?;
+    let mut parts = request_line.split_whitespace();
+    let (method, path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+
+    let mut content_length = 0usize;
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse()?;
+            }
+        }
+    }
+
+    let (status, response) = if content_length > MAX_BODY {
+        (
+            "413 Payload Too Large",
+            format!("Bodies are limited to {MAX_BODY} bytes").into_bytes(),
+        )
+    } else {
+        let mut body = vec![0u8; content_length];
+        reader.read_exact(&mut body).await?;
+
+        match (method, path) {
+            ("GET", "/health") => ("200 OK", b"ok".to_vec()),
+            ("POST", "/prove-tx") => match prove(&body, &prover).await {
+                Ok(proven) => ("200 OK", proven),
+                Err((status, message)) => (status, message.into_bytes()),
+            },
+            _ => ("404 Not Found", Vec::new()),
+        }
+    };
+    println!("  {method} {path} → {status}");
+
+    let mut stream = reader.into_inner();
+    stream
+        .write_all(
+            format!(
+                "HTTP/1.1 {status}\r\ncontent-type: application/octet-stream\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                response.len()
+            )
+            .as_bytes(),
+        )
+        .await?;
+    stream.write_all(&response).await?;
+    Ok(())
+}
+
+/// The proven transaction, or the status and message to answer with.
+async fn prove(
+    body: &[u8],
+    prover: &dyn ProofProvider<DefaultDB>,
+) -> Result<Vec<u8>, (&'static str, String)> {
+    const BAD_REQUEST: &str = "400 Bad Request";
+    const INTERNAL_ERROR: &str = "500 Internal Server Error";
+
+    let mut body = body;
+    let tx: Transaction<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB> =
+        deserialize(&mut body)
+            .map_err(|e| (BAD_REQUEST, format!("Not an unproven transaction: {e}")))?;
+    let cost_model: CostModel =
+        deserialize(&mut body).map_err(|e| (BAD_REQUEST, format!("Not a cost model: {e}")))?;
+
+    let resolver = bboard::get_resolver().map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+    let proven = prover
+        .prove(tx, StdRng::from_entropy(), resolver, &cost_model)
+        .await
+        .map_err(|e| (INTERNAL_ERROR, e.to_string()))?;
+
+    serialize(&proven).map_err(|e| (INTERNAL_ERROR, e.to_string()))
+}
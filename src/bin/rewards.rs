@@ -0,0 +1,101 @@
+//! # Midnight Block Rewards
+//!
+//! Shows the rewards waiting to be claimed by our unshielded address and
+//! claims them, confirming the payout from the `ClaimRewards` and
+//! `PayoutMinted` events.
+//!
+//! ## Usage:
+//! ```text
+//! rewards show  [--seed <hex>]
+//! rewards claim [--seed <hex>] [--amount <n>] [--rng-seed <seed>]
+//! ```
+//!
+//! `claim` takes everything unclaimed unless `--amount` is given.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../rewards.rs"]
+mod rewards;
+#[path = "../rng.rs"]
+mod rng;
+
+use midnight_node_ledger_helpers::*;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// Wallet seed (hex-encoded, 32 bytes)
+const WALLET_SEED_HEX: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Block Rewards ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let seed: WalletSeed = args
+        .value_or("seed", WALLET_SEED_HEX)
+        .parse()
+        .map_err(|e| format!("Invalid --seed: {e:?}"))?;
+    let address = rewards::reward_address(seed);
+    println!("  Address: {}", hex::encode(address.0 .0));
+
+    let (api, _rpc) = chain::connect(NODE_URL).await?;
+    let unclaimed = rewards::fetch_unclaimed(&api, &address).await?;
+    println!("  Unclaimed: {unclaimed}");
+
+    match args.subcommand() {
+        Some("show") => Ok(()),
+        Some("claim") => claim(&args, &api, seed, unclaimed).await,
+        _ => Err("Usage: rewards <show|claim> ...".into()),
+    }
+}
+
+// ─── Claim ───────────────────────────────────────────────────────────────────
+
+async fn claim(
+    args: &cli::Args,
+    api: &chain::Api,
+    seed: WalletSeed,
+    unclaimed: u128,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let value = match args.value("amount") {
+        Some(amount) => amount.parse()?,
+        None => unclaimed,
+    };
+    if value == 0 {
+        println!("\nNothing to claim");
+        return Ok(());
+    }
+    if value > unclaimed {
+        return Err(format!("Cannot claim {value}; only {unclaimed} is unclaimed").into());
+    }
+
+    let network_id = chain::fetch_network_id(api).await?;
+    println!("✓ Network ID: {network_id}");
+    chain::ensure_ledger_compatible(api).await?;
+
+    let mut rng = rng::rng(rng::seed_from_args(args), "claim-rewards");
+    let tx = rewards::build_claim(&network_id, seed, value, &mut rng);
+    let serialized = serialize(&tx)?;
+    println!("✓ Claim for {value} built ({} bytes)", serialized.len());
+
+    let Some(submitted) = chain::submit_transaction(api, serialized).await? else {
+        return Ok(());
+    };
+
+    let claimed = rewards::print_reward_events(&submitted.events);
+    if claimed != value {
+        println!("⚠️  Claimed {claimed}, expected {value}");
+    }
+
+    let address = rewards::reward_address(seed);
+    let remaining = rewards::fetch_unclaimed(api, &address).await?;
+    println!("\n  Unclaimed now: {remaining}");
+    Ok(())
+}
@@ -0,0 +1,81 @@
+//! # Midnight System Transactions
+//!
+//! Lists the system transactions in a range of finalized blocks with their
+//! type, origin and effects (see `systx.rs`), for auditing how a chain's
+//! ledger state was changed outside user transactions.
+//!
+//! ## Usage:
+//! ```text
+//! system_txs [--from <block>] [--to <block>] [--kind <kind>]
+//! ```
+//!
+//! The range defaults to genesis through the finalized head. `--kind`
+//! matches the printed kind, e.g. `parameter update`.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../systx.rs"]
+mod systx;
+
+use midnight_node_ledger_helpers::*;
+
+use chain::RawTransaction;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight System Transactions ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+
+    let from: u64 = args.value_or("from", "0").parse()?;
+    let to: u64 = match args.value("to") {
+        Some(to) => to.parse()?,
+        None => api.blocks().at_latest().await?.number() as u64,
+    };
+    let kind = args.value("kind");
+
+    let mut found = 0;
+    for block_num in from..=to {
+        let block = chain::fetch_block(&api, &rpc, block_num).await?;
+        for block_tx in &block.transactions {
+            let RawTransaction::System(bytes) = &block_tx.raw else {
+                continue;
+            };
+            let tx = match deserialize::<SystemTransaction, _>(&mut bytes.as_slice()) {
+                Ok(tx) => tx,
+                Err(e) => {
+                    eprintln!(
+                        "  ⚠ Skipping undecodable system transaction in block {} extrinsic {}: {e}",
+                        block.number, block_tx.extrinsic_index
+                    );
+                    continue;
+                }
+            };
+            let origin = systx::Origin::of(block.number, block_tx);
+            let classified = systx::classify(&tx, Some(origin));
+            if kind.is_some_and(|kind| kind != classified.kind) {
+                continue;
+            }
+
+            println!(
+                "\n=== Block {} extrinsic {} ===",
+                block.number, block_tx.extrinsic_index
+            );
+            systx::print(&classified);
+            found += 1;
+        }
+    }
+
+    println!("\n✓ {found} system transactions in blocks {from}..={to}");
+    Ok(())
+}
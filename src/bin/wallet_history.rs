@@ -0,0 +1,96 @@
+//! # Midnight Wallet History
+//!
+//! Replays the chain and records every transaction that touched the wallet
+//! (see `history.rs`), then prints it or exports it as CSV or JSON.
+//!
+//! ## Usage:
+//! ```text
+//! wallet_history [--seed <hex>] [--out <file.csv|file.json>]
+//!                [--verify-root-every <n>]
+//!                [--bootstrap-from <block> [--bootstrap-contract <address>]]
+//! ```
+//!
+//! The export format follows the `--out` extension.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../history.rs"]
+mod history;
+#[path = "../tokens.rs"]
+mod tokens;
+
+use midnight_node_ledger_helpers::*;
+use std::sync::Arc;
+
+use history::HistoryRecorder;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// Wallet seed (hex-encoded, 32 bytes)
+const WALLET_SEED_HEX: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Wallet History ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let seed: WalletSeed = args
+        .value_or("seed", WALLET_SEED_HEX)
+        .parse()
+        .map_err(|e| format!("Invalid --seed: {e:?}"))?;
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+    let network_id = chain::fetch_network_id(&api).await?;
+    println!("✓ Network ID: {network_id}");
+
+    let context = Arc::new(LedgerContext::<DefaultDB>::new_from_wallet_seeds(
+        &network_id,
+        &[seed],
+    ));
+    let mut recorder = HistoryRecorder::new(seed);
+    chain::sync_observed(
+        &api,
+        &rpc,
+        &context,
+        &chain::SyncOptions::from_args(&args)?,
+        &mut recorder,
+    )
+    .await?;
+
+    let records = recorder.records;
+    println!("\n=== History ({} transactions) ===", records.len());
+    for record in &records {
+        let net: Vec<String> = record
+            .net()
+            .iter()
+            .map(|(token, amount)| format!("{amount:+} {token}"))
+            .collect();
+        println!(
+            "  #{:<8} {:<13} {}  fee {}",
+            record.block_number,
+            record.kind,
+            net.join(", "),
+            record.fee
+        );
+    }
+
+    if let Some(out) = args.value("out") {
+        if out.ends_with(".csv") {
+            history::write_csv(out, &records)?;
+        } else if out.ends_with(".json") {
+            history::write_json(out, &records)?;
+        } else {
+            return Err(format!("Unknown export format for {out}; use .csv or .json").into());
+        }
+        println!("\n✓ Exported to {out}");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+//! # Midnight Contract Watcher
+//!
+//! Follows finalized blocks and reports every call to one contract: the
+//! operation, the values its transcripts read back to the caller, and the
+//! contract's ledger state after the block (shared by every call in it).
+//! BBoard ledgers are decoded into their message and owner; other contracts
+//! print the raw state.
+//!
+//! ## Usage:
+//! ```text
+//! watch_contract <name|address> [--from <block>] [--bboard]
+//! ```
+//!
+//! `--bboard` is implied when the contract is given by the name `bboard`.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../bboard.rs"]
+mod bboard;
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../events.rs"]
+mod events;
+#[path = "../keys.rs"]
+mod keys;
+#[path = "../manifest.rs"]
+mod manifest;
+#[path = "../witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::*;
+use subxt::ext::futures::StreamExt;
+
+use bboard::BBoardLedger;
+use chain::{FetchedBlock, RawTransaction};
+use events::{BlockEvent, EventFilter, EventKind};
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Contract Watcher ===\n");
+
+    let args = cli::Args::from_env(&["bboard"]);
+    let contract = args
+        .positional(0)
+        .ok_or("Usage: watch_contract <name|address>")?;
+    let is_bboard = args.flag("bboard") || contract == "bboard";
+    let from = args.value("from").map(str::parse).transpose()?;
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+    let address = manifest::resolve_contract(&api, contract).await?;
+    println!("  Contract: {}", hex::encode(address.0 .0));
+    println!("✓ Watching finalized blocks (Ctrl-C to stop)");
+
+    let filter = EventFilter::contract(address).with_kind(EventKind::ContractCall);
+    let mut stream = std::pin::pin!(events::subscribe(api.clone(), rpc.clone(), from, filter));
+    // Calls in the same block share one fetch.
+    let mut block: Option<FetchedBlock> = None;
+    // A transaction calling the contract twice emits one event per call, and
+    // `report_call` already covers them all.
+    let mut last_reported = None;
+
+    while let Some(event) = stream.next().await {
+        let event = event?;
+        let reported = Some((event.block_number, event.event.tx_hash()));
+        if reported == last_reported {
+            continue;
+        }
+        last_reported = reported;
+        if block.as_ref().map(|b| b.number) != Some(event.block_number) {
+            block = Some(chain::fetch_block(&api, &rpc, event.block_number).await?);
+        }
+        let block = block.as_ref().expect("fetched above");
+
+        report_call(block, &event, &address)?;
+
+        let state = chain::fetch_contract_state_at(&api, &address, block.hash).await?;
+        let ledger = state.data.get();
+        // Several calls in one block all see the state the block left
+        // behind, not the state right after their own call.
+        println!("  State after block {}:", block.number);
+        if is_bboard {
+            print_bboard(&BBoardLedger::decode(&ledger)?);
+        } else {
+            println!("  {ledger:#?}");
+        }
+    }
+
+    println!("\n⚠️  Finalized block subscription closed");
+    Ok(())
+}
+
+// ─── Reporting ───────────────────────────────────────────────────────────────
+
+/// Prints the entry point and transcript results of each call to `address`
+/// made by the transaction behind `event`.
+fn report_call(
+    block: &FetchedBlock,
+    event: &BlockEvent,
+    address: &ContractAddress,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let tx_hash = event.event.tx_hash();
+    println!(
+        "\n=== Block {} (tx 0x{}) ===",
+        event.block_number,
+        hex::encode(tx_hash.unwrap_or_default())
+    );
+
+    for block_tx in &block.transactions {
+        let RawTransaction::Standard(bytes) = &block_tx.raw else {
+            continue;
+        };
+        let tx = match deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice()) {
+            Ok(tx) => tx,
+            Err(e) => {
+                eprintln!(
+                    "  ⚠ Skipping undecodable transaction 0x{}: {e}",
+                    hex::encode(block_tx.extrinsic_hash.0)
+                );
+                continue;
+            }
+        };
+        if Some(tx.transaction_hash().0 .0) != tx_hash {
+            continue;
+        }
+        let Transaction::Standard(stx) = &tx else {
+            continue;
+        };
+
+        for (_, intent) in stx.intents.iter() {
+            for action in intent.actions.iter() {
+                let ContractAction::Call(call) = &action else {
+                    continue;
+                };
+                if call.address != *address {
+                    continue;
+                }
+                println!(
+                    "  Operation: {}",
+                    String::from_utf8_lossy(&call.entry_point.0)
+                );
+                let results: Vec<_> = [&call.guaranteed_transcript, &call.fallible_transcript]
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|transcript| transcript.program.iter())
+                    .filter_map(|op| match op {
+                        Op::Popeq { result, .. } => Some(result.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                if results.is_empty() {
+                    println!("  Results:   none");
+                }
+                for result in results {
+                    println!("  Result:    {result:?}");
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    println!("  ⚠️ Transaction not found in block");
+    Ok(())
+}
+
+fn print_bboard(ledger: &BBoardLedger) {
+    println!("  Board:     {:?}", ledger.state);
+    match &ledger.message {
+        Some(message) => println!("  Message:   {message}"),
+        None => println!("  Message:   (none)"),
+    }
+    println!("  Owner:     {}", hex::encode(ledger.owner));
+    println!("  Sequence:  {}", ledger.sequence);
+}
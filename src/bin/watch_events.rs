@@ -0,0 +1,62 @@
+//! # Midnight Event Watcher
+//!
+//! Follows finalized blocks and prints each Midnight pallet event (see
+//! `events.rs`) with the block and extrinsic it came from.
+//!
+//! ## Usage:
+//! ```text
+//! watch_events [--from <block>] [--contract <name|address>]... [--kind <kind>]...
+//! ```
+//!
+//! Contracts can be referenced by their name in the network's deployment
+//! registry (see `deploy_contract`) or by hex address.
+//!
+//! Kinds: tx-applied, tx-partial-success, contract-deploy, contract-call,
+//! contract-maintain, unshielded-tokens, payout-minted, claim-rewards.
+
+#[path = "../midnight.rs"]
+mod midnight;
+
+#[path = "../chain.rs"]
+mod chain;
+#[path = "../cli.rs"]
+mod cli;
+#[path = "../events.rs"]
+mod events;
+#[path = "../manifest.rs"]
+mod manifest;
+
+use subxt::ext::futures::StreamExt;
+
+use events::EventFilter;
+
+const NODE_URL: &str = "ws://localhost:9944";
+
+// ─── Main ────────────────────────────────────────────────────────────────────
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== Midnight Event Watcher ===\n");
+
+    let args = cli::Args::from_env(&[]);
+    let from = args.value("from").map(str::parse).transpose()?;
+
+    let (api, rpc) = chain::connect(NODE_URL).await?;
+    let filter = EventFilter::from_args(&api, &args).await?;
+    println!("✓ Watching finalized blocks (Ctrl-C to stop)\n");
+
+    let mut stream = std::pin::pin!(events::subscribe(api, rpc, from, filter));
+    while let Some(event) = stream.next().await {
+        let event = event?;
+        let extrinsic = event
+            .extrinsic_index
+            .map_or("-".to_string(), |i| i.to_string());
+        println!(
+            "  #{:<8} ext {extrinsic:<3} {}",
+            event.block_number, event.event
+        );
+    }
+
+    println!("\n⚠️  Finalized block subscription closed");
+    Ok(())
+}
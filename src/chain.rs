@@ -0,0 +1,1077 @@
+//! # Node access shared by the binaries
+//!
+//! Connecting to the node, replaying finalized blocks into a `LedgerContext`
+//! and submitting serialized Midnight transactions. Each binary pulls this in
+//! with `#[path = "../chain.rs"] mod chain;` next to its `midnight` module.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use subxt::backend::legacy::{rpc_methods::NumberOrHex, LegacyRpcMethods};
+use subxt::backend::rpc::RpcClient;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::events::{EventDetails, Events};
+use subxt::ext::codec::Decode;
+use subxt::ext::scale_value::{Composite, Value as DynamicValue, ValueDef};
+
+use crate::cli;
+use crate::midnight;
+
+// Use our local subxt-generated types for decoding extrinsics and events.
+use crate::midnight::api::runtime_types::midnight_node_runtime::RuntimeCall;
+use crate::midnight::api::runtime_types::pallet_midnight::pallet::Call as MidnightCall;
+use crate::midnight::api::runtime_types::pallet_midnight_system::pallet::Call as MidnightSystemCall;
+use crate::midnight::api::runtime_types::pallet_timestamp::pallet::Call as TimestampCall;
+
+// The event wrapper type (implements StaticEvent, unlike the raw runtime type)
+use crate::midnight::api::midnight_system::events::SystemTransactionApplied;
+
+pub type Api = subxt::OnlineClient<subxt::PolkadotConfig>;
+
+/// The node's legacy RPC methods, plus what [`fetch_block`] caches about the
+/// node's runtimes. Clones share the caches; another node gets its own.
+#[derive(Clone)]
+pub struct Rpc {
+    methods: LegacyRpcMethods<subxt::PolkadotConfig>,
+    runtimes: Arc<RuntimeCache>,
+}
+
+impl Rpc {
+    pub fn new(client: RpcClient) -> Self {
+        Self {
+            methods: LegacyRpcMethods::new(client),
+            runtimes: Arc::default(),
+        }
+    }
+}
+
+impl std::ops::Deref for Rpc {
+    type Target = LegacyRpcMethods<subxt::PolkadotConfig>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.methods
+    }
+}
+
+// ─── Connection ──────────────────────────────────────────────────────────────
+
+pub async fn connect(
+    node_url: &str,
+) -> Result<(Api, Rpc), Box<dyn std::error::Error + Send + Sync>> {
+    println!("\nConnecting to {node_url}...");
+    let api = Api::from_url(node_url).await?;
+    let rpc_client = RpcClient::from_insecure_url(node_url).await?;
+    let rpc = Rpc::new(rpc_client);
+    println!("✓ Connected");
+    Ok((api, rpc))
+}
+
+pub async fn fetch_network_id(
+    api: &Api,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let network_id: String = api
+        .runtime_api()
+        .at_latest()
+        .await?
+        .call(
+            midnight::api::apis()
+                .midnight_runtime_api()
+                .get_network_id(),
+        )
+        .await?;
+    Ok(network_id)
+}
+
+// ─── Chain info ──────────────────────────────────────────────────────────────
+
+/// Major version of `midnight-ledger` that the `midnight-node-ledger-helpers`
+/// rev pinned in `Cargo.toml` is built against; bump both together.
+/// Transactions built with it are only valid on a node running the same
+/// major version.
+pub const SUPPORTED_LEDGER_MAJOR: u32 = 7;
+
+/// What the node reports about its runtime and ledger at one block.
+pub struct ChainInfo {
+    pub block_number: u64,
+    pub block_hash: subxt::utils::H256,
+    pub network_id: String,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+    /// As reported by `get_ledger_version`, e.g. `ledger-7.0.0`.
+    pub ledger_version: String,
+    pub ledger_parameters: LedgerParameters,
+    pub zswap_root: Vec<u8>,
+}
+
+impl ChainInfo {
+    pub async fn fetch(api: &Api) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let block = api.blocks().at_latest().await?;
+        let runtime_api = api.runtime_api().at(block.hash());
+        let apis = midnight::api::apis().midnight_runtime_api();
+
+        let network_id = runtime_api.call(apis.get_network_id()).await?;
+        let ledger_version = runtime_api.call(apis.get_ledger_version()).await?;
+        let parameter_bytes = runtime_api
+            .call(apis.get_ledger_parameters())
+            .await?
+            .map_err(|e| format!("get_ledger_parameters failed: {e:?}"))?;
+        let zswap_root = runtime_api
+            .call(apis.get_zswap_state_root())
+            .await?
+            .map_err(|e| format!("get_zswap_state_root failed: {e:?}"))?;
+        let runtime_version = api.runtime_version();
+
+        Ok(Self {
+            block_number: block.number() as u64,
+            block_hash: block.hash(),
+            network_id,
+            spec_version: runtime_version.spec_version,
+            transaction_version: runtime_version.transaction_version,
+            ledger_version: String::from_utf8_lossy(&ledger_version).to_string(),
+            ledger_parameters: deserialize(&mut parameter_bytes.as_slice())?,
+            zswap_root,
+        })
+    }
+
+    /// The major version in `ledger_version`, if it carries one.
+    pub fn ledger_major(&self) -> Option<u32> {
+        self.ledger_version
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|part| !part.is_empty())?
+            .parse()
+            .ok()
+    }
+
+    pub fn check_ledger_compatible(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        match self.ledger_major() {
+            Some(SUPPORTED_LEDGER_MAJOR) => Ok(()),
+            _ => Err(format!(
+                "Node runs ledger {} but this build supports ledger {SUPPORTED_LEDGER_MAJOR}.x; \
+                 refusing to build transactions",
+                self.ledger_version
+            )
+            .into()),
+        }
+    }
+}
+
+/// Fails unless the node's ledger version matches [`SUPPORTED_LEDGER_MAJOR`].
+/// Binaries that build transactions call this before building.
+pub async fn ensure_ledger_compatible(
+    api: &Api,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let info = ChainInfo::fetch(api).await?;
+    info.check_ledger_compatible()?;
+    println!("✓ Ledger version: {}", info.ledger_version);
+    Ok(())
+}
+
+// ─── Metadata ────────────────────────────────────────────────────────────────
+
+/// Fails unless the node's metadata matches the metadata `midnight.rs` was
+/// generated from. With stale bindings extrinsics stop decoding, and replay
+/// would miss the transactions they carry.
+pub fn ensure_codegen_valid(api: &Api) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if !midnight::api::is_codegen_valid_for(&api.metadata()) {
+        return Err(
+            "The node's runtime metadata differs from the one src/midnight.rs was \
+                    generated from; run `metadata check` to see what changed and \
+                    `metadata refresh` to regenerate the bindings"
+                .into(),
+        );
+    }
+    println!("✓ Bindings match the node's metadata");
+    Ok(())
+}
+
+// ─── Contract state ──────────────────────────────────────────────────────────
+
+pub fn parse_contract_address(
+    hex_str: &str,
+) -> Result<ContractAddress, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = hex::decode(hex_str.trim_start_matches("0x"))?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| format!("Contract address must be 32 bytes: {hex_str}"))?;
+    Ok(ContractAddress(HashOutput(bytes)))
+}
+
+/// Decodes the `contract_address` bytes carried by the Midnight pallet's
+/// contract events. The node emits the serialized address; raw 32-byte
+/// addresses are accepted as well.
+pub fn decode_event_contract_address(bytes: &[u8]) -> Option<ContractAddress> {
+    if let Ok(address) = deserialize::<ContractAddress, _>(&mut &bytes[..]) {
+        return Some(address);
+    }
+    let raw: [u8; 32] = bytes.try_into().ok()?;
+    Some(ContractAddress(HashOutput(raw)))
+}
+
+/// Fetches the current on-chain state of a contract through the runtime API.
+pub async fn fetch_contract_state(
+    api: &Api,
+    address: &ContractAddress,
+) -> Result<ContractState<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
+    let latest = api.blocks().at_latest().await?.hash();
+    fetch_contract_state_at(api, address, latest).await
+}
+
+/// The state of a contract as of block `block_hash`.
+pub async fn fetch_contract_state_at(
+    api: &Api,
+    address: &ContractAddress,
+    block_hash: subxt::utils::H256,
+) -> Result<ContractState<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
+    let state_bytes = api
+        .runtime_api()
+        .at(block_hash)
+        .call(
+            midnight::api::apis()
+                .midnight_runtime_api()
+                .get_contract_state(address.0 .0.to_vec()),
+        )
+        .await?
+        .map_err(|e| format!("get_contract_state failed: {e:?}"))?;
+    let state = deserialize::<ContractState<DefaultDB>, _>(&mut state_bytes.as_slice())?;
+    Ok(state)
+}
+
+// ─── Blocks ──────────────────────────────────────────────────────────────────
+
+/// Serialized Midnight transaction bytes as found in a block.
+#[derive(Clone, Debug)]
+pub enum RawTransaction {
+    /// `send_mn_transaction` payload, a `FinalizedTransaction`.
+    Standard(Vec<u8>),
+    /// A `SystemTransaction`, from the genesis extrinsics or a
+    /// `SystemTransactionApplied` event.
+    System(Vec<u8>),
+}
+
+pub struct BlockTransaction {
+    /// Index of the extrinsic that carried or triggered the transaction.
+    pub extrinsic_index: u32,
+    pub extrinsic_hash: subxt::utils::H256,
+    /// `Pallet.call` of that extrinsic, e.g. `Council.close` for a system
+    /// transaction applied by a governance motion.
+    pub call: String,
+    pub raw: RawTransaction,
+}
+
+pub struct FetchedBlock {
+    pub number: u64,
+    pub hash: subxt::utils::H256,
+    pub parent_hash: subxt::utils::H256,
+    pub timestamp_ms: Option<u64>,
+    pub transactions: Vec<BlockTransaction>,
+    /// Every event of the block, for callers that want more than the
+    /// system transactions.
+    pub events: Events<subxt::PolkadotConfig>,
+}
+
+/// Fetches finalized block `block_num` and extracts its timestamp and
+/// Midnight transactions, in extrinsic order.
+///
+/// Blocks executed by an older runtime are decoded with that runtime's
+/// metadata (see [`historic_metadata`]). Calls our bindings can no longer
+/// decode statically are read dynamically by pallet, call and field name.
+pub async fn fetch_block(
+    api: &Api,
+    rpc: &Rpc,
+    block_num: u64,
+) -> Result<FetchedBlock, Box<dyn std::error::Error + Send + Sync>> {
+    // Get block hash
+    let block_hash = rpc
+        .chain_get_block_hash(Some(NumberOrHex::Number(block_num)))
+        .await?
+        .ok_or_else(|| format!("Block hash missing for block {block_num}"))?;
+
+    let body = rpc
+        .chain_get_block(Some(block_hash))
+        .await?
+        .ok_or_else(|| format!("Block {block_num} missing"))?;
+    let parent_hash = body.block.header.parent_hash;
+
+    // A block is executed by the runtime of its parent's state; a `set_code`
+    // block is still the old runtime's. Genesis has no parent.
+    let state_hash = if block_num == 0 {
+        block_hash
+    } else {
+        parent_hash
+    };
+    let spec_version = spec_version_at(rpc, state_hash).await?;
+    let metadata = if spec_version == api.runtime_version().spec_version {
+        api.metadata()
+    } else {
+        historic_metadata(rpc, state_hash, spec_version).await?
+    };
+
+    let raw_extrinsics = body.block.extrinsics.into_iter().map(|e| e.0).collect();
+    let extrinsics = subxt::ext::subxt_core::blocks::decode_from::<subxt::PolkadotConfig>(
+        raw_extrinsics,
+        metadata.clone(),
+    )?;
+    let mut calls = Vec::new();
+    for ext in extrinsics.iter() {
+        let (pallet, variant) = (ext.pallet_name()?, ext.variant_name()?);
+        calls.push(ExtrinsicCall {
+            index: ext.index(),
+            hash: ext.hash(),
+            name: format!("{pallet}.{variant}"),
+            data: call_data(
+                pallet,
+                variant,
+                ext.as_root_extrinsic::<RuntimeCall>().ok(),
+                || ext.field_values().ok(),
+            )
+            .ok_or_else(|| undecodable(block_num, ext.index(), pallet, variant))?,
+        });
+    }
+    let event_bytes = rpc
+        .state_get_storage(&SYSTEM_EVENTS_KEY, Some(block_hash))
+        .await?
+        .unwrap_or_default();
+    let events = Events::decode_from(event_bytes, metadata);
+
+    // Unless this block upgraded the runtime, its child runs the same one
+    if !runtime_upgraded(&events) {
+        *rpc.runtimes
+            .last_spec_version
+            .lock()
+            .expect("spec version cache poisoned") = Some((block_hash, spec_version));
+    }
+
+    let mut timestamp_ms: Option<u64> = None;
+    let mut transactions = Vec::new();
+
+    for call in calls {
+        match call.data {
+            CallData::Timestamp(now) => timestamp_ms = Some(now),
+            CallData::Transaction(midnight_tx) => transactions.push(BlockTransaction {
+                extrinsic_index: call.index,
+                extrinsic_hash: call.hash,
+                call: call.name.clone(),
+                raw: RawTransaction::Standard(midnight_tx),
+            }),
+            // Genesis block: extract system transactions directly from extrinsics
+            // (genesis has no events since events are emitted during block execution)
+            CallData::SystemTransaction(midnight_system_tx) if block_num == 0 => {
+                transactions.push(BlockTransaction {
+                    extrinsic_index: call.index,
+                    extrinsic_hash: call.hash,
+                    call: call.name.clone(),
+                    raw: RawTransaction::System(midnight_system_tx),
+                })
+            }
+            _ => {}
+        }
+
+        // Non-genesis blocks: extract system transactions from events.
+        // This handles system txs regardless of how they were triggered
+        // (direct calls, governance-wrapped, cNight observation, etc.)
+        if block_num > 0 {
+            let ext_events = ExtrinsicEvents::new(call.hash, call.index, events.clone());
+            for ev in ext_events.iter().filter_map(Result::ok) {
+                if let Some(bytes) = system_transaction_bytes(&ev) {
+                    transactions.push(BlockTransaction {
+                        extrinsic_index: call.index,
+                        extrinsic_hash: call.hash,
+                        call: call.name.clone(),
+                        raw: RawTransaction::System(bytes),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(FetchedBlock {
+        number: block_num,
+        hash: block_hash,
+        parent_hash,
+        timestamp_ms,
+        transactions,
+        events,
+    })
+}
+
+// ─── Runtime versions ────────────────────────────────────────────────────────
+
+/// `twox128("System") ++ twox128("Events")`
+const SYSTEM_EVENTS_KEY: [u8; 32] = [
+    0x26, 0xaa, 0x39, 0x4e, 0xea, 0x56, 0x30, 0xe0, 0x7c, 0x48, 0xae, 0x0c, 0x95, 0x58, 0xce, 0xf7,
+    0x80, 0xd4, 0x1e, 0x5e, 0x16, 0x05, 0x67, 0x65, 0xbc, 0x84, 0x61, 0x85, 0x10, 0x72, 0xc9, 0xd7,
+];
+
+#[derive(Default)]
+struct RuntimeCache {
+    /// The last fetched block whose child runs the same runtime, with that
+    /// runtime's spec version, so sequential fetches skip the version lookup.
+    last_spec_version: Mutex<Option<(subxt::utils::H256, u32)>>,
+    /// Metadata of runtimes older than the node's current one, by spec version.
+    historic_metadata: Mutex<HashMap<u32, subxt::Metadata>>,
+}
+
+/// Spec version of the runtime in the state after block `block_hash`.
+async fn spec_version_at(
+    rpc: &Rpc,
+    block_hash: subxt::utils::H256,
+) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
+    let cached = *rpc
+        .runtimes
+        .last_spec_version
+        .lock()
+        .expect("spec version cache poisoned");
+    if let Some((hash, spec_version)) = cached {
+        if hash == block_hash {
+            return Ok(spec_version);
+        }
+    }
+    Ok(rpc
+        .state_get_runtime_version(Some(block_hash))
+        .await?
+        .spec_version)
+}
+
+/// The metadata of runtime `spec_version`, read from the node at a block
+/// whose state holds that runtime and cached in `rpc`.
+pub async fn historic_metadata(
+    rpc: &Rpc,
+    block_hash: subxt::utils::H256,
+    spec_version: u32,
+) -> Result<subxt::Metadata, Box<dyn std::error::Error + Send + Sync>> {
+    let cache = &rpc.runtimes.historic_metadata;
+    if let Some(metadata) = cache
+        .lock()
+        .expect("metadata cache poisoned")
+        .get(&spec_version)
+    {
+        return Ok(metadata.clone());
+    }
+
+    let raw = rpc.state_get_metadata(Some(block_hash)).await?.into_raw();
+    let metadata = subxt::Metadata::decode(&mut raw.as_slice())?;
+    println!("  Decoding runtime {spec_version} blocks with their own metadata");
+    cache
+        .lock()
+        .expect("metadata cache poisoned")
+        .insert(spec_version, metadata.clone());
+    Ok(metadata)
+}
+
+/// The last runtime upgrade applied, as `(spec version, spec name)`.
+pub async fn last_runtime_upgrade(
+    api: &Api,
+) -> Result<Option<(u32, String)>, Box<dyn std::error::Error + Send + Sync>> {
+    let upgrade = api
+        .storage()
+        .at_latest()
+        .await?
+        .fetch(&midnight::api::storage().system().last_runtime_upgrade())
+        .await?;
+    Ok(upgrade.map(|info| (info.spec_version, info.spec_name)))
+}
+
+/// Whether `events` hold `System.CodeUpdated`, i.e. the block ran a
+/// `set_code` (directly or through governance) and the next block is built
+/// by the new runtime.
+pub fn runtime_upgraded(events: &Events<subxt::PolkadotConfig>) -> bool {
+    events
+        .iter()
+        .filter_map(Result::ok)
+        .any(|ev| ev.pallet_name() == "System" && ev.variant_name() == "CodeUpdated")
+}
+
+// ─── Call decoding ───────────────────────────────────────────────────────────
+
+/// The parts of an extrinsic `fetch_block` uses.
+struct ExtrinsicCall {
+    index: u32,
+    hash: subxt::utils::H256,
+    /// `Pallet.call`
+    name: String,
+    data: CallData,
+}
+
+enum CallData {
+    /// `Timestamp.set`
+    Timestamp(u64),
+    /// `Midnight.send_mn_transaction`
+    Transaction(Vec<u8>),
+    /// `MidnightSystem.send_mn_system_transaction`
+    SystemTransaction(Vec<u8>),
+    Other,
+}
+
+/// Reads the calls we need from the statically decoded `call`, or from the
+/// dynamically decoded `fields` when the static types no longer match.
+/// `None` when one of those calls decodes neither way.
+fn call_data(
+    pallet: &str,
+    variant: &str,
+    call: Option<RuntimeCall>,
+    fields: impl FnOnce() -> Option<Composite<u32>>,
+) -> Option<CallData> {
+    match call {
+        Some(RuntimeCall::Timestamp(TimestampCall::set { now })) => {
+            return Some(CallData::Timestamp(now))
+        }
+        Some(RuntimeCall::Midnight(MidnightCall::send_mn_transaction { midnight_tx })) => {
+            return Some(CallData::Transaction(midnight_tx))
+        }
+        Some(RuntimeCall::MidnightSystem(MidnightSystemCall::send_mn_system_transaction {
+            midnight_system_tx,
+        })) => return Some(CallData::SystemTransaction(midnight_system_tx)),
+        Some(_) => return Some(CallData::Other),
+        None => {}
+    }
+
+    match (pallet, variant) {
+        ("Timestamp", "set") => {
+            let now = find_field(&fields()?, "now")?.as_u128()?;
+            Some(CallData::Timestamp(now as u64))
+        }
+        ("Midnight", "send_mn_transaction") => Some(CallData::Transaction(value_bytes(
+            find_field(&fields()?, "midnight_tx")?,
+        )?)),
+        ("MidnightSystem", "send_mn_system_transaction") => Some(CallData::SystemTransaction(
+            value_bytes(find_field(&fields()?, "midnight_system_tx")?)?,
+        )),
+        _ => Some(CallData::Other),
+    }
+}
+
+fn undecodable(block_num: u64, index: u32, pallet: &str, variant: &str) -> String {
+    format!("Block {block_num} extrinsic {index} ({pallet}.{variant}) does not decode")
+}
+
+/// The payload of a `SystemTransactionApplied` event, decoded statically or
+/// by field name.
+fn system_transaction_bytes(ev: &EventDetails<subxt::PolkadotConfig>) -> Option<Vec<u8>> {
+    if let Ok(Some(event)) = ev.as_event::<SystemTransactionApplied>() {
+        return Some(event.0.serialized_system_transaction);
+    }
+    if ev.pallet_name() != "MidnightSystem" || ev.variant_name() != "SystemTransactionApplied" {
+        return None;
+    }
+    value_bytes(find_field(
+        &ev.field_values().ok()?,
+        "serialized_system_transaction",
+    )?)
+}
+
+/// Finds field `name` in `fields` or any composite nested in them.
+fn find_field<'a>(fields: &'a Composite<u32>, name: &str) -> Option<&'a DynamicValue<u32>> {
+    let nested = |value: &'a DynamicValue<u32>| match &value.value {
+        ValueDef::Composite(composite) => find_field(composite, name),
+        ValueDef::Variant(variant) => find_field(&variant.values, name),
+        _ => None,
+    };
+    match fields {
+        Composite::Named(named) => named.iter().find_map(|(field, value)| {
+            if field == name {
+                Some(value)
+            } else {
+                nested(value)
+            }
+        }),
+        Composite::Unnamed(values) => values.iter().find_map(nested),
+    }
+}
+
+/// A `Vec<u8>` decoded as a dynamic value.
+fn value_bytes(value: &DynamicValue<u32>) -> Option<Vec<u8>> {
+    let ValueDef::Composite(Composite::Unnamed(items)) = &value.value else {
+        return None;
+    };
+    items
+        .iter()
+        .map(|item| item.as_u128().and_then(|byte| u8::try_from(byte).ok()))
+        .collect()
+}
+
+// ─── Block replay ────────────────────────────────────────────────────────────
+
+/// Fetches every finalized block and replays it into `context`.
+///
+/// This mirrors what the toolkit's fetcher does:
+///   For each block → decode extrinsics → extract timestamp + midnight txs
+///   → tagged_deserialize → context.update_from_block()
+///
+/// The local zswap root is checked against the node's at the end, and every
+/// `verify_every` blocks if given (see [`verify_zswap_root`]).
+///
+/// Returns the finalized height that was replayed up to.
+pub async fn replay_blocks(
+    api: &Api,
+    rpc: &Rpc,
+    context: &LedgerContext<DefaultDB>,
+    verify_every: Option<u64>,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    replay_range(api, rpc, context, 0, verify_every, true, &mut ()).await
+}
+
+/// Hooks run by [`replay_range`] around every block, e.g. to record what the
+/// block changed for a wallet.
+pub trait ReplayObserver {
+    fn before_block(
+        &mut self,
+        _context: &LedgerContext<DefaultDB>,
+        _block: &FetchedBlock,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+
+    fn after_block(
+        &mut self,
+        _context: &LedgerContext<DefaultDB>,
+        _block: &FetchedBlock,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Ok(())
+    }
+}
+
+/// No hooks.
+impl ReplayObserver for () {}
+
+/// [`replay_blocks`] starting at block `from` instead of genesis; `context`
+/// must already hold the state as of block `from - 1`. Without `verify_roots`
+/// the zswap root is never checked, for states that can't match the node's.
+pub async fn replay_range(
+    api: &Api,
+    rpc: &Rpc,
+    context: &LedgerContext<DefaultDB>,
+    from: u64,
+    verify_every: Option<u64>,
+    verify_roots: bool,
+    observer: &mut dyn ReplayObserver,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    println!("\nFetching and replaying blocks...");
+    let finalized_height = api.blocks().at_latest().await?.number() as u64;
+    println!("  Finalized height: {finalized_height}");
+    if let Some((spec_version, spec_name)) = last_runtime_upgrade(api).await? {
+        println!("  Runtime: {spec_name} {spec_version} (older blocks decode with their own)");
+    }
+
+    // Local roots since the last successful check
+    let mut unverified: Vec<LocalRoot> = Vec::new();
+
+    for block_num in from..=finalized_height {
+        let block = fetch_block(api, rpc, block_num).await?;
+        if runtime_upgraded(&block.events) {
+            println!("  Block {block_num}: runtime upgraded; later blocks use the new metadata");
+        }
+
+        let mut txs: Vec<SerdeTransaction<Signature, ProofMarker, DefaultDB>> = Vec::new();
+        for tx in &block.transactions {
+            match &tx.raw {
+                RawTransaction::Standard(bytes) => {
+                    match deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice()) {
+                        Ok(tx) => txs.push(SerdeTransaction::Midnight(tx)),
+                        Err(e) => {
+                            eprintln!("  ⚠ Block {block_num}: failed to deserialize mn tx: {e}");
+                        }
+                    }
+                }
+                RawTransaction::System(bytes) => {
+                    match deserialize::<SystemTransaction, _>(&mut bytes.as_slice()) {
+                        Ok(tx) => txs.push(SerdeTransaction::System(tx)),
+                        Err(e) => {
+                            eprintln!(
+                                "  ⚠ Block {block_num}: failed to deserialize system tx: {e}"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        // Build BlockContext (same as toolkit's compute_task.rs)
+        let timestamp_ms = block
+            .timestamp_ms
+            .ok_or_else(|| format!("Block {block_num} has no timestamp extrinsic"))?;
+        let block_context = BlockContext {
+            tblock: Timestamp::from_secs(timestamp_ms / 1000),
+            tblock_err: 30,
+            parent_block_hash: HashOutput(block.parent_hash.0),
+        };
+
+        if from > 0 {
+            check_wallet_spends_known(context, &txs, block_num, from)?;
+        }
+
+        // Replay into LedgerContext
+        observer.before_block(context, &block)?;
+        context.update_from_block(txs, block_context, None);
+        observer.after_block(context, &block)?;
+
+        if verify_roots {
+            unverified.push(LocalRoot {
+                block_number: block_num,
+                block_hash: block.hash,
+                root: local_zswap_root(context)?,
+            });
+            let due = verify_every.is_some_and(|n| n > 0 && block_num % n == 0);
+            if due || block_num == finalized_height {
+                verify_zswap_root(api, &unverified).await?;
+                unverified.clear();
+            }
+        }
+
+        // Progress indicator
+        if block_num % 100 == 0 || block_num == finalized_height {
+            print!("\r  Replayed block {block_num}/{finalized_height}");
+        }
+    }
+    println!("\n✓ All blocks replayed");
+    if verify_roots {
+        println!("✓ Zswap root matches the node");
+    }
+
+    Ok(finalized_height)
+}
+
+/// After a bootstrap the wallets' unshielded UTXOs from before `from` are
+/// unknown, and so is the DUST that NIGHT generated. Fails on the first
+/// spend, by a wallet's key, of a UTXO the local state doesn't hold, rather
+/// than replaying on with wrong balances.
+fn check_wallet_spends_known(
+    context: &LedgerContext<DefaultDB>,
+    txs: &[SerdeTransaction<Signature, ProofMarker, DefaultDB>],
+    block_num: u64,
+    from: u64,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let owners: Vec<VerifyingKey> = context
+        .wallets
+        .lock()
+        .expect("wallets lock poisoned")
+        .values()
+        .map(|wallet| wallet.unshielded.signing_key().verifying_key())
+        .collect();
+    let state = context
+        .ledger_state
+        .lock()
+        .expect("ledger state lock poisoned");
+    for tx in txs {
+        let SerdeTransaction::Midnight(Transaction::Standard(stx)) = tx else {
+            continue;
+        };
+        for (_, intent) in stx.intents.iter() {
+            let offers = [
+                &intent.guaranteed_unshielded_offer,
+                &intent.fallible_unshielded_offer,
+            ];
+            for spend in offers
+                .into_iter()
+                .flatten()
+                .flat_map(|offer| offer.inputs.iter())
+            {
+                if owners.contains(&spend.owner)
+                    && !state.utxo.utxos.contains_key(&Utxo::from(spend.clone()))
+                {
+                    return Err(format!(
+                        "Block {block_num} spends a wallet UTXO created before block {from}, \
+                         which bootstrapping doesn't restore; bootstrap from a block before \
+                         the wallet's first unshielded or DUST activity, or replay from genesis"
+                    )
+                    .into());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+// ─── Bootstrap ───────────────────────────────────────────────────────────────
+
+/// How to bring a fresh `LedgerContext` up to the chain tip.
+#[derive(Default)]
+pub struct SyncOptions {
+    /// Check the zswap root every n blocks, not only at the end.
+    pub verify_every: Option<u64>,
+    /// Skip replaying blocks before this one (see [`bootstrap`]).
+    pub bootstrap_from: Option<u64>,
+    /// Bootstrap from this contract's zswap state instead of the global one.
+    /// That state's root never matches the node's, so roots go unchecked.
+    pub bootstrap_contract: Option<ContractAddress>,
+}
+
+impl SyncOptions {
+    /// Reads `--verify-root-every <n>`, `--bootstrap-from <block>` and
+    /// `--bootstrap-contract <address>`.
+    pub fn from_args(args: &cli::Args) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            verify_every: args
+                .value("verify-root-every")
+                .map(str::parse)
+                .transpose()?,
+            bootstrap_from: args.value("bootstrap-from").map(str::parse).transpose()?,
+            bootstrap_contract: args
+                .value("bootstrap-contract")
+                .map(parse_contract_address)
+                .transpose()?,
+        })
+    }
+}
+
+/// Replays or bootstraps `context` up to the finalized tip, as `options` say.
+pub async fn sync(
+    api: &Api,
+    rpc: &Rpc,
+    context: &LedgerContext<DefaultDB>,
+    options: &SyncOptions,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    sync_observed(api, rpc, context, options, &mut ()).await
+}
+
+/// [`sync`] with `observer` run around every replayed block.
+pub async fn sync_observed(
+    api: &Api,
+    rpc: &Rpc,
+    context: &LedgerContext<DefaultDB>,
+    options: &SyncOptions,
+    observer: &mut dyn ReplayObserver,
+) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+    ensure_codegen_valid(api)?;
+    let from = match options.bootstrap_from {
+        Some(from) => {
+            bootstrap(api, rpc, context, from, options.bootstrap_contract.as_ref()).await?;
+            from
+        }
+        None => 0,
+    };
+    let verify_roots = options.bootstrap_contract.is_none();
+    if !verify_roots {
+        println!("⚠️  Contract-scoped zswap state: roots are not checked against the node");
+    }
+    replay_range(
+        api,
+        rpc,
+        context,
+        from,
+        options.verify_every,
+        verify_roots,
+        observer,
+    )
+    .await
+}
+
+/// Seeds `context` with the node's zswap chain state as of block `from - 1`,
+/// so replay can start at `from`: only blocks since the wallet's first
+/// transaction need scanning.
+///
+/// Only the zswap (shielded) state is seeded. Unshielded UTXOs and DUST
+/// created before `from` stay unknown, so pick a block before the wallet
+/// first received anything; replay fails if a wallet spends one of them.
+///
+/// Without `contract`, the state is asked for with an empty address and its
+/// root must equal the node's `get_zswap_state_root`, which proves it is the
+/// global tree. With `contract`, the state only holds that contract's
+/// commitments, so it is seeded unchecked, later roots can't be verified,
+/// and the wallets' coin trees are left alone: positions in a contract's
+/// tree would make every wallet spend unprovable.
+pub async fn bootstrap(
+    api: &Api,
+    rpc: &Rpc,
+    context: &LedgerContext<DefaultDB>,
+    from: u64,
+    contract: Option<&ContractAddress>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let at = from.checked_sub(1).ok_or("Cannot bootstrap from genesis")?;
+    println!("\nBootstrapping zswap state at block {at}...");
+
+    let block_hash = rpc
+        .chain_get_block_hash(Some(NumberOrHex::Number(at)))
+        .await?
+        .ok_or_else(|| format!("Block hash missing for block {at}"))?;
+
+    let address = contract.map(|a| a.0 .0.to_vec()).unwrap_or_default();
+    let bytes = api
+        .runtime_api()
+        .at(block_hash)
+        .call(
+            midnight::api::apis()
+                .midnight_runtime_api()
+                .get_zswap_chain_state(address),
+        )
+        .await?
+        .map_err(|e| format!("get_zswap_chain_state failed: {e:?}"))?;
+    let zswap: ZswapChainState<DefaultDB> = deserialize(&mut bytes.as_slice())?;
+
+    if contract.is_none() {
+        let root = serialize(&zswap.coin_coms.rehash().root())?;
+        if root != node_zswap_root(api, block_hash).await? {
+            return Err(format!(
+                "get_zswap_chain_state with an empty address did not return the global \
+                 zswap state at block {at}; bootstrap from a contract or replay from genesis"
+            )
+            .into());
+        }
+        println!("✓ Bootstrapped state matches the node's zswap root");
+    }
+
+    if contract.is_none() {
+        // Wallets track coin positions in their own copy of the tree
+        for wallet in context
+            .wallets
+            .lock()
+            .expect("wallets lock poisoned")
+            .values_mut()
+        {
+            wallet.shielded.state.merkle_tree = zswap.coin_coms.clone();
+            wallet.shielded.state.first_free = zswap.first_free;
+        }
+    } else {
+        eprintln!(
+            "  ⚠ Contract-scoped bootstrap: wallet coin trees are not seeded, so shielded \
+             wallet balances and spends from this state are wrong"
+        );
+    }
+    let mut state = context
+        .ledger_state
+        .lock()
+        .expect("ledger state lock poisoned");
+    state.zswap = Sp::new(zswap);
+
+    println!("✓ Seeded zswap state ({} bytes)", bytes.len());
+    Ok(())
+}
+
+// ─── Zswap root verification ─────────────────────────────────────────────────
+
+/// The zswap root computed locally after replaying a block.
+pub struct LocalRoot {
+    pub block_number: u64,
+    pub block_hash: subxt::utils::H256,
+    /// Serialized commitment tree root, as `get_zswap_state_root` returns it.
+    pub root: Vec<u8>,
+}
+
+pub fn local_zswap_root(
+    context: &LedgerContext<DefaultDB>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let state = context
+        .ledger_state
+        .lock()
+        .expect("ledger state lock poisoned");
+    let root = state.zswap.coin_coms.rehash().root();
+    Ok(serialize(&root)?)
+}
+
+pub async fn node_zswap_root(
+    api: &Api,
+    block_hash: subxt::utils::H256,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let root = api
+        .runtime_api()
+        .at(block_hash)
+        .call(
+            midnight::api::apis()
+                .midnight_runtime_api()
+                .get_zswap_state_root(),
+        )
+        .await?
+        .map_err(|e| format!("get_zswap_state_root failed: {e:?}"))?;
+    Ok(root)
+}
+
+/// Checks the last of `roots` against the node. On a mismatch, bisects
+/// `roots` for the first block whose root differs (a diverged tree never
+/// converges again) and fails naming it.
+pub async fn verify_zswap_root(
+    api: &Api,
+    roots: &[LocalRoot],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let Some(last) = roots.last() else {
+        return Ok(());
+    };
+    if node_zswap_root(api, last.block_hash).await? == last.root {
+        return Ok(());
+    }
+
+    // roots[..lo] match, roots[hi] does not
+    let (mut lo, mut hi) = (0, roots.len() - 1);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if node_zswap_root(api, roots[mid].block_hash).await? == roots[mid].root {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+
+    let first = &roots[hi];
+    let node_root = node_zswap_root(api, first.block_hash).await?;
+    Err(format!(
+        "Zswap root diverged from the node at block {} (0x{}): local {}, node {}",
+        first.block_number,
+        hex::encode(first.block_hash.0),
+        hex::encode(&first.root),
+        hex::encode(node_root)
+    )
+    .into())
+}
+
+// ─── Submit via subxt ────────────────────────────────────────────────────────
+
+/// A transaction that made it into a finalized block.
+pub struct Submitted {
+    pub block_hash: subxt::utils::H256,
+    pub events: ExtrinsicEvents<subxt::PolkadotConfig>,
+}
+
+impl Submitted {
+    pub async fn block_number(
+        &self,
+        api: &Api,
+    ) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(api.blocks().at(self.block_hash).await?.number() as u64)
+    }
+}
+
+/// Submits a serialized Midnight transaction and waits for finalization.
+///
+/// Returns the finalized block and the extrinsic's events when it succeeded,
+/// `None` if it was included but failed.
+pub async fn submit_transaction(
+    api: &Api,
+    serialized_tx: Vec<u8>,
+) -> Result<Option<Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+    println!("\n=== Submitting Transaction ===\n");
+
+    let tx_payload = midnight::api::tx()
+        .midnight()
+        .send_mn_transaction(serialized_tx);
+
+    let unsigned = api.tx().create_unsigned(&tx_payload)?;
+    let progress = unsigned.submit_and_watch().await?;
+    watch_submission(progress).await
+}
+
+/// Waits for a submitted extrinsic to be finalized and reports the outcome.
+pub async fn watch_submission(
+    progress: subxt::tx::TxProgress<subxt::PolkadotConfig, Api>,
+) -> Result<Option<Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+    println!("✓ Transaction submitted!");
+    println!(
+        "  Extrinsic hash: 0x{}",
+        hex::encode(progress.extrinsic_hash().0)
+    );
+    println!("  Waiting for finalization...\n");
+
+    let in_block = progress.wait_for_finalized().await?;
+    let block_hash = in_block.block_hash();
+    match in_block.wait_for_success().await {
+        Ok(events) => {
+            println!("✅ Transaction finalized successfully!");
+            Ok(Some(Submitted { block_hash, events }))
+        }
+        Err(e) => {
+            println!("⚠️  Transaction failed:");
+            println!("   Error: {e:?}");
+            Ok(None)
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! # Minimal command-line parsing
+//!
+//! The binaries take a subcommand, positional arguments and `--option value`
+//! pairs. Options listed as switches take no value.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+pub struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, Option<String>)>,
+}
+
+impl Args {
+    /// Parses `std::env::args()`, treating the names in `switches` as flags
+    /// without a value (e.g. `&["mock-proofs"]` for `--mock-proofs`).
+    pub fn from_env(switches: &[&str]) -> Self {
+        Self::parse(std::env::args().skip(1), switches)
+    }
+
+    pub fn parse(args: impl IntoIterator<Item = String>, switches: &[&str]) -> Self {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                positional.push(arg);
+                continue;
+            };
+            if let Some((name, value)) = name.split_once('=') {
+                options.push((name.to_string(), Some(value.to_string())));
+            } else if switches.contains(&name) {
+                options.push((name.to_string(), None));
+            } else {
+                options.push((name.to_string(), args.next()));
+            }
+        }
+
+        Self {
+            positional,
+            options,
+        }
+    }
+
+    /// The first positional argument, used as the subcommand name.
+    pub fn subcommand(&self) -> Option<&str> {
+        self.positional.first().map(String::as_str)
+    }
+
+    /// Positional argument `index`, counting the subcommand as 0.
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.positional.get(index).map(String::as_str)
+    }
+
+    pub fn flag(&self, name: &str) -> bool {
+        self.options.iter().any(|(n, _)| n == name)
+    }
+
+    /// The last value given for `--name`.
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(n, _)| n == name)
+            .and_then(|(_, v)| v.as_deref())
+    }
+
+    /// Every value given for a repeatable `--name`.
+    pub fn values(&self, name: &str) -> Vec<&str> {
+        self.options
+            .iter()
+            .filter(|(n, _)| n == name)
+            .filter_map(|(_, v)| v.as_deref())
+            .collect()
+    }
+
+    pub fn value_or<'a>(&'a self, name: &str, default: &'a str) -> &'a str {
+        self.value(name).unwrap_or(default)
+    }
+
+    pub fn require(&self, name: &str) -> Result<&str, Box<dyn std::error::Error + Send + Sync>> {
+        self.value(name)
+            .ok_or_else(|| format!("Missing required option --{name}").into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string()), &["mock-proofs"])
+    }
+
+    #[test]
+    fn positionals_keep_their_order() {
+        let args = parse(&["submit", "--seed", "01", "update.hex"]);
+        assert_eq!(args.subcommand(), Some("submit"));
+        assert_eq!(args.positional(1), Some("update.hex"));
+        assert_eq!(args.positional(2), None);
+    }
+
+    #[test]
+    fn options_take_the_next_argument_or_an_inline_value() {
+        let args = parse(&["--from", "10", "--to=20"]);
+        assert_eq!(args.value("from"), Some("10"));
+        assert_eq!(args.value("to"), Some("20"));
+        assert_eq!(args.subcommand(), None);
+    }
+
+    #[test]
+    fn switches_take_no_value() {
+        let args = parse(&["--mock-proofs", "deploy"]);
+        assert!(args.flag("mock-proofs"));
+        assert_eq!(args.value("mock-proofs"), None);
+        assert_eq!(args.subcommand(), Some("deploy"));
+    }
+
+    #[test]
+    fn repeated_options_keep_every_value_and_the_last_wins() {
+        let args = parse(&["--add-op", "a=x", "--add-op", "b=y"]);
+        assert_eq!(args.values("add-op"), ["a=x", "b=y"]);
+        assert_eq!(args.value("add-op"), Some("b=y"));
+    }
+
+    #[test]
+    fn missing_values_fall_back_or_fail() {
+        let args = parse(&["--seed"]);
+        assert!(args.flag("seed"));
+        assert_eq!(args.value_or("seed", "default"), "default");
+        assert!(args.require("seed").is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! # Maintenance committee configuration
+//!
+//! Collects the verifying keys of a contract's maintenance committee from
+//! command-line options and checks the M-of-N threshold against them.
+//!
+//! Members can be given as wallet seeds (`--committee-seed`), hex-encoded
+//! verifying keys (`--committee-key`) or a file (`--committee-file`) holding
+//! one member per line, either `seed:<hex>` or a plain verifying key hex.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::wallet::UnshieldedWallet;
+use midnight_node_ledger_helpers::*;
+
+use crate::cli;
+
+pub struct CommitteeConfig {
+    pub members: Vec<VerifyingKey>,
+    pub threshold: u32,
+}
+
+impl CommitteeConfig {
+    /// Reads the committee options, falling back to a single-member committee
+    /// of `default_seed` with threshold 1 when none are given.
+    pub fn from_args(
+        args: &cli::Args,
+        default_seed: WalletSeed,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut members = members_from_args(args)?;
+        if members.is_empty() {
+            members.push(verifying_key_from_seed_bytes(default_seed));
+        }
+
+        let threshold = match args.value("threshold") {
+            Some(threshold) => threshold.parse()?,
+            None if members.len() == 1 => 1,
+            None => return Err("--threshold is required for a multi-member committee".into()),
+        };
+
+        let config = Self { members, threshold };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.members.is_empty() {
+            return Err("Committee must have at least one member".into());
+        }
+        if self.threshold == 0 || self.threshold as usize > self.members.len() {
+            return Err(format!(
+                "Threshold {} is invalid for a committee of {}",
+                self.threshold,
+                self.members.len()
+            )
+            .into());
+        }
+        for (i, member) in self.members.iter().enumerate() {
+            if self.members[..i].contains(member) {
+                return Err(format!("Committee member {i} is listed twice").into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Hex-encoded member keys, in committee order.
+    pub fn member_hexes(&self) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        self.members
+            .iter()
+            .map(|member| Ok(hex::encode(serialize(member)?)))
+            .collect()
+    }
+}
+
+/// Every member given through `--committee-seed`, `--committee-key` and
+/// `--committee-file`, in that order.
+pub fn members_from_args(
+    args: &cli::Args,
+) -> Result<Vec<VerifyingKey>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut members = Vec::new();
+    for seed in args.values("committee-seed") {
+        members.push(verifying_key_from_seed(seed)?);
+    }
+    for key in args.values("committee-key") {
+        members.push(parse_verifying_key(key)?);
+    }
+    if let Some(path) = args.value("committee-file") {
+        members.extend(read_committee_file(path)?);
+    }
+    Ok(members)
+}
+
+pub fn verifying_key_from_seed(
+    seed_hex: &str,
+) -> Result<VerifyingKey, Box<dyn std::error::Error + Send + Sync>> {
+    let seed: WalletSeed = seed_hex
+        .parse()
+        .map_err(|_| format!("Invalid committee seed hex: {seed_hex}"))?;
+    Ok(verifying_key_from_seed_bytes(seed))
+}
+
+fn verifying_key_from_seed_bytes(seed: WalletSeed) -> VerifyingKey {
+    UnshieldedWallet::default(seed)
+        .signing_key()
+        .verifying_key()
+        .clone()
+}
+
+pub fn parse_verifying_key(
+    key_hex: &str,
+) -> Result<VerifyingKey, Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = hex::decode(key_hex.trim_start_matches("0x"))?;
+    deserialize(&mut bytes.as_slice())
+        .map_err(|e| format!("Invalid verifying key {key_hex}: {e}").into())
+}
+
+pub fn read_committee_file(
+    path: &str,
+) -> Result<Vec<VerifyingKey>, Box<dyn std::error::Error + Send + Sync>> {
+    let contents = std::fs::read_to_string(path)?;
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.strip_prefix("seed:") {
+            Some(seed) => verifying_key_from_seed(seed.trim()),
+            None => parse_verifying_key(line),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(seed: u8) -> VerifyingKey {
+        verifying_key_from_seed(&hex::encode([seed; 32])).unwrap()
+    }
+
+    fn config(members: &[u8], threshold: u32) -> CommitteeConfig {
+        CommitteeConfig {
+            members: members.iter().copied().map(member).collect(),
+            threshold,
+        }
+    }
+
+    #[test]
+    fn thresholds_within_the_committee_are_valid() {
+        assert!(config(&[1], 1).validate().is_ok());
+        assert!(config(&[1, 2, 3], 2).validate().is_ok());
+        assert!(config(&[1, 2, 3], 3).validate().is_ok());
+    }
+
+    #[test]
+    fn zero_or_oversized_thresholds_are_rejected() {
+        assert!(config(&[1, 2], 0).validate().is_err());
+        assert!(config(&[1, 2], 3).validate().is_err());
+    }
+
+    #[test]
+    fn empty_committees_are_rejected() {
+        assert!(config(&[], 0).validate().is_err());
+    }
+
+    #[test]
+    fn duplicate_members_are_rejected() {
+        let err = config(&[1, 2, 1], 2).validate().unwrap_err();
+        assert!(err.to_string().contains("member 2"), "{err}");
+    }
+
+    #[test]
+    fn members_parse_from_seeds_and_keys() {
+        let key = hex::encode(serialize(&member(2)).unwrap());
+        let args = cli::Args::parse(
+            [
+                "--committee-seed".to_string(),
+                hex::encode([1u8; 32]),
+                "--committee-key".to_string(),
+                key,
+            ],
+            &[],
+        );
+        assert_eq!(members_from_args(&args).unwrap(), [member(1), member(2)]);
+    }
+}
@@ -0,0 +1,392 @@
+//! # Midnight pallet events
+//!
+//! [`MidnightEvent`] is a decoded Midnight pallet event, and [`subscribe`]
+//! turns the finalized chain into a stream of them with their block context
+//! attached:
+//!
+//! ```text
+//! let filter = EventFilter::from_args(&api, &args).await?;
+//! let mut events = std::pin::pin!(events::subscribe(api, rpc, None, filter));
+//! while let Some(event) = events.next().await {
+//!     let event = event?;
+//!     println!("#{} {}", event.block_number, event.event);
+//! }
+//! ```
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+use std::collections::VecDeque;
+use std::fmt;
+use subxt::events::Phase;
+use subxt::ext::futures::stream::{self, Stream, StreamExt};
+
+use crate::chain::{self, FetchedBlock};
+use crate::cli;
+use crate::manifest;
+use crate::midnight;
+use crate::midnight::api::runtime_types::midnight_node_ledger::common::types::UtxoInfo;
+use crate::midnight::api::runtime_types::pallet_midnight::pallet::Event as PalletEvent;
+
+#[derive(Debug)]
+pub enum MidnightEvent {
+    /// Both the guaranteed and the fallible part of a transaction applied.
+    TxApplied {
+        tx_hash: [u8; 32],
+    },
+    /// Only the guaranteed part applied.
+    TxPartialSuccess {
+        tx_hash: [u8; 32],
+    },
+    ContractDeploy {
+        tx_hash: [u8; 32],
+        address: Option<ContractAddress>,
+    },
+    ContractCall {
+        tx_hash: [u8; 32],
+        address: Option<ContractAddress>,
+    },
+    ContractMaintain {
+        tx_hash: [u8; 32],
+        address: Option<ContractAddress>,
+    },
+    UnshieldedTokens {
+        spent: Vec<UtxoInfo>,
+        created: Vec<UtxoInfo>,
+    },
+    PayoutMinted {
+        amount: u128,
+        receiver: Vec<u8>,
+    },
+    ClaimRewards {
+        tx_hash: [u8; 32],
+        value: u128,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    TxApplied,
+    TxPartialSuccess,
+    ContractDeploy,
+    ContractCall,
+    ContractMaintain,
+    UnshieldedTokens,
+    PayoutMinted,
+    ClaimRewards,
+}
+
+impl EventKind {
+    pub const ALL: &'static [EventKind] = &[
+        EventKind::TxApplied,
+        EventKind::TxPartialSuccess,
+        EventKind::ContractDeploy,
+        EventKind::ContractCall,
+        EventKind::ContractMaintain,
+        EventKind::UnshieldedTokens,
+        EventKind::PayoutMinted,
+        EventKind::ClaimRewards,
+    ];
+
+    /// The name used on the command line, e.g. `contract-call`.
+    pub fn name(self) -> &'static str {
+        match self {
+            EventKind::TxApplied => "tx-applied",
+            EventKind::TxPartialSuccess => "tx-partial-success",
+            EventKind::ContractDeploy => "contract-deploy",
+            EventKind::ContractCall => "contract-call",
+            EventKind::ContractMaintain => "contract-maintain",
+            EventKind::UnshieldedTokens => "unshielded-tokens",
+            EventKind::PayoutMinted => "payout-minted",
+            EventKind::ClaimRewards => "claim-rewards",
+        }
+    }
+}
+
+impl std::str::FromStr for EventKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventKind::ALL
+            .iter()
+            .copied()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = EventKind::ALL.iter().map(|kind| kind.name()).collect();
+                format!(
+                    "Unknown event kind {s}; expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl MidnightEvent {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            MidnightEvent::TxApplied { .. } => EventKind::TxApplied,
+            MidnightEvent::TxPartialSuccess { .. } => EventKind::TxPartialSuccess,
+            MidnightEvent::ContractDeploy { .. } => EventKind::ContractDeploy,
+            MidnightEvent::ContractCall { .. } => EventKind::ContractCall,
+            MidnightEvent::ContractMaintain { .. } => EventKind::ContractMaintain,
+            MidnightEvent::UnshieldedTokens { .. } => EventKind::UnshieldedTokens,
+            MidnightEvent::PayoutMinted { .. } => EventKind::PayoutMinted,
+            MidnightEvent::ClaimRewards { .. } => EventKind::ClaimRewards,
+        }
+    }
+
+    pub fn tx_hash(&self) -> Option<[u8; 32]> {
+        match self {
+            MidnightEvent::TxApplied { tx_hash }
+            | MidnightEvent::TxPartialSuccess { tx_hash }
+            | MidnightEvent::ContractDeploy { tx_hash, .. }
+            | MidnightEvent::ContractCall { tx_hash, .. }
+            | MidnightEvent::ContractMaintain { tx_hash, .. }
+            | MidnightEvent::ClaimRewards { tx_hash, .. } => Some(*tx_hash),
+            MidnightEvent::UnshieldedTokens { .. } | MidnightEvent::PayoutMinted { .. } => None,
+        }
+    }
+
+    /// The contract a deploy, call or maintenance event is about.
+    pub fn contract_address(&self) -> Option<&ContractAddress> {
+        match self {
+            MidnightEvent::ContractDeploy { address, .. }
+            | MidnightEvent::ContractCall { address, .. }
+            | MidnightEvent::ContractMaintain { address, .. } => address.as_ref(),
+            _ => None,
+        }
+    }
+
+    fn from_pallet_event(event: PalletEvent) -> Self {
+        let address = chain::decode_event_contract_address;
+        match event {
+            PalletEvent::TxApplied(d) => MidnightEvent::TxApplied { tx_hash: d.tx_hash },
+            PalletEvent::TxPartialSuccess(d) => {
+                MidnightEvent::TxPartialSuccess { tx_hash: d.tx_hash }
+            }
+            PalletEvent::ContractDeploy(d) => MidnightEvent::ContractDeploy {
+                tx_hash: d.tx_hash,
+                address: address(&d.contract_address),
+            },
+            PalletEvent::ContractCall(d) => MidnightEvent::ContractCall {
+                tx_hash: d.tx_hash,
+                address: address(&d.contract_address),
+            },
+            PalletEvent::ContractMaintain(d) => MidnightEvent::ContractMaintain {
+                tx_hash: d.tx_hash,
+                address: address(&d.contract_address),
+            },
+            PalletEvent::UnshieldedTokens(d) => MidnightEvent::UnshieldedTokens {
+                spent: d.spent,
+                created: d.created,
+            },
+            PalletEvent::PayoutMinted(d) => MidnightEvent::PayoutMinted {
+                amount: d.amount,
+                receiver: d.receiver,
+            },
+            PalletEvent::ClaimRewards(d) => MidnightEvent::ClaimRewards {
+                tx_hash: d.tx_hash,
+                value: d.value,
+            },
+        }
+    }
+}
+
+impl fmt::Display for MidnightEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind().name())?;
+        if let Some(address) = self.contract_address() {
+            write!(f, " contract {}", hex::encode(address.0 .0))?;
+        }
+        match self {
+            MidnightEvent::UnshieldedTokens { spent, created } => {
+                write!(f, " ({} spent, {} created)", spent.len(), created.len())?
+            }
+            MidnightEvent::PayoutMinted { amount, receiver } => {
+                write!(f, " {amount} to 0x{}", hex::encode(receiver))?
+            }
+            MidnightEvent::ClaimRewards { value, .. } => write!(f, " {value}")?,
+            _ => {}
+        }
+        if let Some(tx_hash) = self.tx_hash() {
+            write!(f, " tx 0x{}", hex::encode(tx_hash))?;
+        }
+        Ok(())
+    }
+}
+
+/// A Midnight event with the block and extrinsic it came from.
+#[derive(Debug)]
+pub struct BlockEvent {
+    pub block_number: u64,
+    pub block_hash: subxt::utils::H256,
+    pub timestamp_ms: Option<u64>,
+    /// `None` for events emitted outside an extrinsic.
+    pub extrinsic_index: Option<u32>,
+    pub event_index: u32,
+    pub event: MidnightEvent,
+}
+
+// ─── Filtering ───────────────────────────────────────────────────────────────
+
+/// Which events a subscriber wants; empty lists match everything.
+#[derive(Debug, Clone, Default)]
+pub struct EventFilter {
+    pub contracts: Vec<ContractAddress>,
+    pub kinds: Vec<EventKind>,
+}
+
+impl EventFilter {
+    /// Reads the repeatable `--contract <name|address>` and `--kind <name>`
+    /// options; contract names are resolved through the network's registry.
+    pub async fn from_args(
+        api: &chain::Api,
+        args: &cli::Args,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let mut contracts = Vec::new();
+        for contract in args.values("contract") {
+            contracts.push(manifest::resolve_contract(api, contract).await?);
+        }
+        let kinds = args
+            .values("kind")
+            .into_iter()
+            .map(str::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { contracts, kinds })
+    }
+
+    pub fn contract(address: ContractAddress) -> Self {
+        Self {
+            contracts: vec![address],
+            kinds: Vec::new(),
+        }
+    }
+
+    pub fn with_kind(mut self, kind: EventKind) -> Self {
+        self.kinds.push(kind);
+        self
+    }
+
+    /// A contract filter drops events that aren't about a contract.
+    pub fn matches(&self, event: &MidnightEvent) -> bool {
+        let kind_ok = self.kinds.is_empty() || self.kinds.contains(&event.kind());
+        let contract_ok = self.contracts.is_empty()
+            || event
+                .contract_address()
+                .is_some_and(|address| self.contracts.contains(address));
+        kind_ok && contract_ok
+    }
+}
+
+// ─── Decoding ────────────────────────────────────────────────────────────────
+
+/// Decodes the Midnight pallet events of `block` that pass `filter`.
+pub fn decode_block(
+    block: &FetchedBlock,
+    filter: &EventFilter,
+) -> Result<Vec<BlockEvent>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut decoded = Vec::new();
+    for ev in block.events.iter() {
+        let ev = ev?;
+        // Events of older runtimes may not match our bindings; skip those
+        // rather than end the stream
+        let event = match ev.as_root_event::<midnight::api::Event>() {
+            Ok(midnight::api::Event::Midnight(event)) => event,
+            Ok(_) => continue,
+            Err(e) => {
+                if ev.pallet_name() == "Midnight" {
+                    eprintln!(
+                        "  ⚠ Block {}: skipping undecodable Midnight.{} event: {e}",
+                        block.number,
+                        ev.variant_name()
+                    );
+                }
+                continue;
+            }
+        };
+        let event = MidnightEvent::from_pallet_event(event);
+        if !filter.matches(&event) {
+            continue;
+        }
+        decoded.push(BlockEvent {
+            block_number: block.number,
+            block_hash: block.hash,
+            timestamp_ms: block.timestamp_ms,
+            extrinsic_index: match ev.phase() {
+                Phase::ApplyExtrinsic(i) => Some(i),
+                _ => None,
+            },
+            event_index: ev.index(),
+            event,
+        });
+    }
+    Ok(decoded)
+}
+
+// ─── Subscription ────────────────────────────────────────────────────────────
+
+type FinalizedHeads =
+    subxt::backend::StreamOfResults<subxt::blocks::Block<subxt::PolkadotConfig, chain::Api>>;
+
+struct SubscriptionState {
+    api: chain::Api,
+    rpc: chain::Rpc,
+    filter: EventFilter,
+    /// Next block to decode; `None` until the first finalized head arrives.
+    next: Option<u64>,
+    heads: Option<FinalizedHeads>,
+    pending: VecDeque<BlockEvent>,
+}
+
+/// Streams the Midnight events of every finalized block from `from`
+/// (default: the next finalized block) that pass `filter`, in order.
+///
+/// Blocks are fetched by number, so none are skipped when finalization jumps
+/// several blocks at once. The stream ends after the first error.
+pub fn subscribe(
+    api: chain::Api,
+    rpc: chain::Rpc,
+    from: Option<u64>,
+    filter: EventFilter,
+) -> impl Stream<Item = Result<BlockEvent, Box<dyn std::error::Error + Send + Sync>>> {
+    let state = SubscriptionState {
+        api,
+        rpc,
+        filter,
+        next: from,
+        heads: None,
+        pending: VecDeque::new(),
+    };
+    stream::try_unfold(state, |mut state| async move {
+        next_event(&mut state)
+            .await
+            .map(|event| event.map(|event| (event, state)))
+    })
+}
+
+async fn next_event(
+    state: &mut SubscriptionState,
+) -> Result<Option<BlockEvent>, Box<dyn std::error::Error + Send + Sync>> {
+    loop {
+        if let Some(event) = state.pending.pop_front() {
+            return Ok(Some(event));
+        }
+        if state.heads.is_none() {
+            state.heads = Some(state.api.blocks().subscribe_finalized().await?);
+        }
+        let heads = state.heads.as_mut().expect("subscribed above");
+        let Some(head) = heads.next().await else {
+            return Ok(None);
+        };
+        let head = head?.number() as u64;
+
+        let mut next = state.next.unwrap_or(head);
+        while next <= head {
+            let block = chain::fetch_block(&state.api, &state.rpc, next).await?;
+            state.pending.extend(decode_block(&block, &state.filter)?);
+            next += 1;
+        }
+        state.next = Some(next);
+    }
+}
@@ -0,0 +1,223 @@
+//! # On-chain governance
+//!
+//! Two collective bodies, the Council and the Technical Committee, vote on
+//! proposals (`propose` / `vote` / `close`). A body approves a federated
+//! authority motion by passing a proposal that calls
+//! `FederatedAuthority.motion_approve(call)`. Once enough bodies approved it,
+//! anyone can `motion_close` the motion to dispatch `call`.
+//!
+//! Calls are described as `Pallet.call` plus a JSON object of named
+//! arguments (byte arrays as JSON arrays of numbers), encoded with the
+//! node's metadata and decoded into our `RuntimeCall`.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use subxt::blocks::ExtrinsicEvents;
+use subxt::ext::scale_decode::DecodeAsType;
+use subxt::ext::scale_encode::EncodeAsType;
+use subxt::ext::scale_value::{self, Composite, Primitive, ValueDef};
+use subxt::utils::{AccountId32, H256};
+
+use crate::chain;
+use crate::midnight;
+use crate::midnight::api::runtime_types::midnight_node_runtime::RuntimeCall;
+use crate::midnight::api::runtime_types::pallet_collective::Votes;
+use crate::midnight::api::runtime_types::pallet_federated_authority::pallet::MotionInfo;
+
+/// Pallets whose events `print_governance_events` shows.
+const GOVERNANCE_PALLETS: &[&str] = &["Council", "TechnicalCommittee", "FederatedAuthority"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Body {
+    Council,
+    TechnicalCommittee,
+}
+
+impl Body {
+    pub const ALL: [Body; 2] = [Body::Council, Body::TechnicalCommittee];
+
+    pub fn pallet(self) -> &'static str {
+        match self {
+            Body::Council => "Council",
+            Body::TechnicalCommittee => "TechnicalCommittee",
+        }
+    }
+}
+
+impl std::str::FromStr for Body {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "council" => Ok(Body::Council),
+            "technical-committee" | "tc" => Ok(Body::TechnicalCommittee),
+            _ => Err(format!(
+                "Unknown body {s}; expected council or technical-committee"
+            )),
+        }
+    }
+}
+
+// ─── Calls ───────────────────────────────────────────────────────────────────
+
+/// Builds the call described by `description` (`Pallet.call`) and `args` (a
+/// JSON object of named arguments, or an array for unnamed ones).
+pub fn parse_call(
+    api: &chain::Api,
+    description: &str,
+    args: Option<&str>,
+) -> Result<RuntimeCall, Box<dyn std::error::Error + Send + Sync>> {
+    let (pallet, call) = description
+        .split_once('.')
+        .ok_or_else(|| format!("Calls are given as Pallet.call, not {description}"))?;
+    let fields = match args {
+        Some(json) => {
+            let json: serde_json::Value = serde_json::from_str(json)?;
+            match scale_value::serde::to_value(&json)?.value {
+                ValueDef::Composite(fields) => fields,
+                _ => return Err("Call arguments must be a JSON object or array".into()),
+            }
+        }
+        None => Composite::Unnamed(Vec::new()),
+    };
+    let call_data = api
+        .tx()
+        .call_data(&subxt::dynamic::tx(pallet, call, fields))?;
+    decode_call(api, &call_data)
+}
+
+pub fn decode_call(
+    api: &chain::Api,
+    call_data: &[u8],
+) -> Result<RuntimeCall, Box<dyn std::error::Error + Send + Sync>> {
+    let metadata = api.metadata();
+    let call = RuntimeCall::decode_as_type(
+        &mut &call_data[..],
+        metadata.outer_enums().call_enum_ty(),
+        metadata.types(),
+    )?;
+    Ok(call)
+}
+
+pub fn encode_call(
+    api: &chain::Api,
+    call: &RuntimeCall,
+) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+    let metadata = api.metadata();
+    Ok(call.encode_as_type(metadata.outer_enums().call_enum_ty(), metadata.types())?)
+}
+
+// ─── State ───────────────────────────────────────────────────────────────────
+
+pub struct Proposal {
+    pub hash: H256,
+    /// `None` if the proposal was removed between the two reads.
+    pub call: Option<RuntimeCall>,
+    pub votes: Option<Votes<AccountId32, u32>>,
+}
+
+pub async fn members(
+    api: &chain::Api,
+    body: Body,
+) -> Result<Vec<AccountId32>, Box<dyn std::error::Error + Send + Sync>> {
+    let storage = api.storage().at_latest().await?;
+    let root = midnight::api::storage();
+    let members = match body {
+        Body::Council => storage.fetch(&root.council().members()).await?,
+        Body::TechnicalCommittee => storage.fetch(&root.technical_committee().members()).await?,
+    };
+    Ok(members.unwrap_or_default())
+}
+
+/// The body's open proposals with their calls and votes.
+pub async fn proposals(
+    api: &chain::Api,
+    body: Body,
+) -> Result<Vec<Proposal>, Box<dyn std::error::Error + Send + Sync>> {
+    let storage = api.storage().at_latest().await?;
+    let root = midnight::api::storage();
+    let hashes = match body {
+        Body::Council => storage.fetch(&root.council().proposals()).await?,
+        Body::TechnicalCommittee => {
+            storage
+                .fetch(&root.technical_committee().proposals())
+                .await?
+        }
+    }
+    .map(|hashes| hashes.0)
+    .unwrap_or_default();
+
+    let mut proposals = Vec::new();
+    for hash in hashes {
+        let (call, votes) = match body {
+            Body::Council => (
+                storage.fetch(&root.council().proposal_of(hash)).await?,
+                storage.fetch(&root.council().voting(hash)).await?,
+            ),
+            Body::TechnicalCommittee => (
+                storage
+                    .fetch(&root.technical_committee().proposal_of(hash))
+                    .await?,
+                storage
+                    .fetch(&root.technical_committee().voting(hash))
+                    .await?,
+            ),
+        };
+        proposals.push(Proposal { hash, call, votes });
+    }
+    Ok(proposals)
+}
+
+/// Open federated authority motions by motion hash.
+pub async fn motions(
+    api: &chain::Api,
+) -> Result<Vec<(H256, MotionInfo)>, Box<dyn std::error::Error + Send + Sync>> {
+    let storage = api.storage().at_latest().await?;
+    // Dynamic, so that iterating decodes the motion hash into `entry.keys`;
+    // `motions_iter()` has no key type to decode it into
+    let query = subxt::dynamic::storage(
+        "FederatedAuthority",
+        "Motions",
+        Vec::<scale_value::Value>::new(),
+    );
+    let mut entries = storage.iter(query).await?;
+    let mut motions = Vec::new();
+    while let Some(entry) = subxt::ext::futures::StreamExt::next(&mut entries).await {
+        let entry = entry?;
+        let [key] = entry.keys.as_slice() else {
+            return Err(format!("Expected one Motions key, got {}", entry.keys.len()).into());
+        };
+        let hash: [u8; 32] = value_bytes(key)
+            .try_into()
+            .map_err(|_| format!("Motions key is not a 32-byte hash: {key}"))?;
+        motions.push((H256(hash), entry.value.as_type::<MotionInfo>()?));
+    }
+    Ok(motions)
+}
+
+/// The bytes of a decoded byte array such as `H256`, through any newtype
+/// wrappers around it.
+fn value_bytes(value: &scale_value::Value) -> Vec<u8> {
+    match &value.value {
+        ValueDef::Composite(fields) => fields.values().flat_map(value_bytes).collect(),
+        ValueDef::Primitive(Primitive::U128(byte)) => vec![*byte as u8],
+        _ => Vec::new(),
+    }
+}
+
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+/// Prints the Council, Technical Committee and Federated Authority events of
+/// a submitted extrinsic.
+pub fn print_governance_events(events: &ExtrinsicEvents<subxt::PolkadotConfig>) {
+    for ev in events.iter().filter_map(Result::ok) {
+        if !GOVERNANCE_PALLETS.contains(&ev.pallet_name()) {
+            continue;
+        }
+        match ev.field_values() {
+            Ok(fields) => println!("  {}.{} {fields}", ev.pallet_name(), ev.variant_name()),
+            Err(_) => println!("  {}.{}", ev.pallet_name(), ev.variant_name()),
+        }
+    }
+}
@@ -0,0 +1,421 @@
+//! # Wallet transaction history
+//!
+//! [`HistoryRecorder`] watches replay (as a `chain::ReplayObserver`) and
+//! records every transaction that touched one wallet:
+//!
+//! - shielded coins received or spent, found by diffing the wallet's coins
+//!   around each block and matching commitments and nullifiers to the
+//!   block's transactions
+//! - unshielded UTXOs spent by the wallet's key or paid to its address
+//! - the DUST fee the wallet paid, counting only spends of its own DUST
+//! - the contract deploys, calls and maintenance updates of those
+//!   transactions, so a transaction that only paid fees (e.g. the wallet's
+//!   own contract call) is still recorded
+//!
+//! Outputs back to the wallet of a token it also spent in the same
+//! transaction are counted as change rather than received; outputs of other
+//! tokens stay received.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::wallet::UnshieldedWallet;
+use midnight_node_ledger_helpers::*;
+
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::chain::{FetchedBlock, RawTransaction, ReplayObserver};
+use crate::tokens;
+
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct HistoryRecord {
+    pub block_number: u64,
+    pub timestamp_ms: u64,
+    /// `0x`-prefixed hash of the extrinsic carrying the transaction.
+    pub extrinsic_hash: String,
+    /// `deploy`, `maintain`, `contract-call`, `send`, `receive`, `fee` or
+    /// `claim`.
+    pub kind: String,
+    /// Amounts per token (see `tokens::token_type_hex`).
+    pub received: BTreeMap<String, u128>,
+    pub spent: BTreeMap<String, u128>,
+    pub change: BTreeMap<String, u128>,
+    /// DUST fee paid from the wallet's own DUST.
+    pub fee: u128,
+    /// `call <address hex>:<entry point>`, `deploy <address hex>` or
+    /// `maintain <address hex>` for each contract action.
+    pub contract_actions: Vec<String>,
+}
+
+impl HistoryRecord {
+    /// Received plus change minus spent, per token.
+    pub fn net(&self) -> BTreeMap<String, i128> {
+        let mut net: BTreeMap<String, i128> = BTreeMap::new();
+        for (token, amount) in self.received.iter().chain(&self.change) {
+            *net.entry(token.clone()).or_default() += *amount as i128;
+        }
+        for (token, amount) in &self.spent {
+            *net.entry(token.clone()).or_default() -= *amount as i128;
+        }
+        net
+    }
+
+    fn is_empty(&self) -> bool {
+        self.received.is_empty() && self.spent.is_empty() && self.change.is_empty()
+    }
+}
+
+// ─── Recorder ────────────────────────────────────────────────────────────────
+
+pub struct HistoryRecorder {
+    seed: WalletSeed,
+    owner: VerifyingKey,
+    address: UserAddress,
+    /// The wallet's shielded coins before the current block.
+    coins_before: HashMap<Nullifier, QualifiedCoinInfo>,
+    /// Nullifiers of the wallet's DUST before the current block.
+    dust_before: HashSet<DustNullifier>,
+    pub records: Vec<HistoryRecord>,
+}
+
+impl HistoryRecorder {
+    pub fn new(seed: WalletSeed) -> Self {
+        let owner = UnshieldedWallet::default(seed)
+            .signing_key()
+            .verifying_key();
+        Self {
+            seed,
+            address: UserAddress::from(owner.clone()),
+            owner,
+            coins_before: HashMap::new(),
+            dust_before: HashSet::new(),
+            records: Vec::new(),
+        }
+    }
+
+    fn shielded_coins(
+        &self,
+        context: &LedgerContext<DefaultDB>,
+    ) -> HashMap<Nullifier, QualifiedCoinInfo> {
+        let wallet = context.wallet_from_seed(self.seed);
+        wallet.shielded.state.coins.iter().collect()
+    }
+
+    fn dust_nullifiers(&self, context: &LedgerContext<DefaultDB>) -> HashSet<DustNullifier> {
+        let wallet = context.wallet_from_seed(self.seed);
+        let Some(state) = &wallet.dust.dust_local_state else {
+            return HashSet::new();
+        };
+        state
+            .utxos()
+            .map(|utxo| utxo.nullifier(&wallet.dust.secret_key))
+            .collect()
+    }
+
+    /// What `tx` did to the wallet; `kind` is left empty if nothing.
+    fn record_transaction(
+        &self,
+        tx: &FinalizedTransaction<DefaultDB>,
+        received_coins: &HashMap<Commitment, QualifiedCoinInfo>,
+        spent_coins: &HashMap<Nullifier, QualifiedCoinInfo>,
+    ) -> HistoryRecord {
+        let mut record = HistoryRecord::default();
+        let stx = match tx {
+            Transaction::Standard(stx) => stx,
+            Transaction::ClaimRewards(claim) => {
+                if claim.owner == self.owner {
+                    record.kind = "claim".to_string();
+                    let night = tokens::token_type_hex(&TokenType::Unshielded(NIGHT));
+                    record.received.insert(night, claim.value);
+                }
+                return record;
+            }
+        };
+
+        // Shielded coins
+        let mut offers: Vec<_> = stx
+            .guaranteed_coins
+            .iter()
+            .map(|offer| (**offer).clone())
+            .collect();
+        offers.extend(stx.fallible_coins.iter().map(|(_, offer)| offer));
+        for offer in offers {
+            for output in offer.outputs.iter() {
+                if let Some(coin) = received_coins.get(&output.coin_com) {
+                    let token = tokens::token_type_hex(&TokenType::Shielded(coin.type_));
+                    *record.received.entry(token).or_default() += coin.value;
+                }
+            }
+            for input in offer.inputs.iter() {
+                if let Some(coin) = spent_coins.get(&input.nullifier) {
+                    let token = tokens::token_type_hex(&TokenType::Shielded(coin.type_));
+                    *record.spent.entry(token).or_default() += coin.value;
+                }
+            }
+        }
+
+        // Unshielded UTXOs
+        for (_, intent) in stx.intents.iter() {
+            let offers = [
+                &intent.guaranteed_unshielded_offer,
+                &intent.fallible_unshielded_offer,
+            ];
+            for offer in offers.into_iter().flatten() {
+                for spend in offer
+                    .inputs
+                    .iter()
+                    .filter(|spend| spend.owner == self.owner)
+                {
+                    let token = tokens::token_type_hex(&TokenType::Unshielded(spend.type_));
+                    *record.spent.entry(token).or_default() += spend.value;
+                }
+                for output in offer.outputs.iter().filter(|out| out.owner == self.address) {
+                    let token = tokens::token_type_hex(&TokenType::Unshielded(output.type_));
+                    *record.received.entry(token).or_default() += output.value;
+                }
+            }
+        }
+
+        // DUST the wallet spent on fees
+        for (_, intent) in stx.intents.iter() {
+            if let Some(dust) = &intent.dust_actions {
+                record.fee += dust
+                    .spends
+                    .iter()
+                    .filter(|spend| self.dust_before.contains(&spend.old_nullifier))
+                    .map(|spend| spend.v_fee)
+                    .sum::<u128>();
+            }
+        }
+
+        if record.is_empty() && record.fee == 0 {
+            return record;
+        }
+
+        let (mut deploys, mut maintains, mut calls) = (0, 0, 0);
+        for (_, intent) in stx.intents.iter() {
+            for action in intent.actions.iter() {
+                record.contract_actions.push(match &action {
+                    ContractAction::Call(call) => {
+                        calls += 1;
+                        format!(
+                            "call {}:{}",
+                            hex::encode(call.address.0 .0),
+                            String::from_utf8_lossy(&call.entry_point.0)
+                        )
+                    }
+                    ContractAction::Deploy(deploy) => {
+                        deploys += 1;
+                        format!("deploy {}", hex::encode(deploy.address().0 .0))
+                    }
+                    ContractAction::Maintain(update) => {
+                        maintains += 1;
+                        format!("maintain {}", hex::encode(update.address.0 .0))
+                    }
+                });
+            }
+        }
+
+        // Only outputs of a token the wallet also spent are change; anything
+        // else it got back (e.g. a contract paying out another token) is
+        // still received.
+        let (change, received) = std::mem::take(&mut record.received)
+            .into_iter()
+            .partition(|(token, _)| record.spent.contains_key(token));
+        record.change = change;
+        record.received = received;
+
+        record.kind = if deploys > 0 {
+            "deploy"
+        } else if maintains > 0 {
+            "maintain"
+        } else if calls > 0 {
+            "contract-call"
+        } else if !record.spent.is_empty() {
+            "send"
+        } else if !record.change.is_empty() || !record.received.is_empty() {
+            "receive"
+        } else {
+            "fee"
+        }
+        .to_string();
+        record
+    }
+}
+
+impl ReplayObserver for HistoryRecorder {
+    fn before_block(
+        &mut self,
+        context: &LedgerContext<DefaultDB>,
+        _block: &FetchedBlock,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.coins_before = self.shielded_coins(context);
+        self.dust_before = self.dust_nullifiers(context);
+        Ok(())
+    }
+
+    fn after_block(
+        &mut self,
+        context: &LedgerContext<DefaultDB>,
+        block: &FetchedBlock,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let coins_after = self.shielded_coins(context);
+        let coin_public_key = context.wallet_from_seed(self.seed).shielded.coin_public_key;
+
+        let received_coins: HashMap<Commitment, QualifiedCoinInfo> = coins_after
+            .iter()
+            .filter(|(nullifier, _)| !self.coins_before.contains_key(nullifier))
+            .map(|(_, coin)| {
+                let info: CoinInfo = coin.clone().into();
+                (
+                    info.commitment(&Recipient::User(coin_public_key)),
+                    coin.clone(),
+                )
+            })
+            .collect();
+        let spent_coins: HashMap<Nullifier, QualifiedCoinInfo> = self
+            .coins_before
+            .iter()
+            .filter(|(nullifier, _)| !coins_after.contains_key(nullifier))
+            .map(|(nullifier, coin)| (*nullifier, coin.clone()))
+            .collect();
+
+        for block_tx in &block.transactions {
+            let RawTransaction::Standard(bytes) = &block_tx.raw else {
+                continue;
+            };
+            let tx = match deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut bytes.as_slice())
+            {
+                Ok(tx) => tx,
+                Err(e) => {
+                    eprintln!(
+                        "  ⚠ Skipping undecodable transaction 0x{} in block {}: {e}",
+                        hex::encode(block_tx.extrinsic_hash.0),
+                        block.number
+                    );
+                    continue;
+                }
+            };
+            let record = self.record_transaction(&tx, &received_coins, &spent_coins);
+            if record.kind.is_empty() {
+                continue;
+            }
+            self.records.push(HistoryRecord {
+                block_number: block.number,
+                timestamp_ms: block.timestamp_ms.unwrap_or_default(),
+                extrinsic_hash: format!("0x{}", hex::encode(block_tx.extrinsic_hash.0)),
+                ..record
+            });
+        }
+        Ok(())
+    }
+}
+
+// ─── Export ──────────────────────────────────────────────────────────────────
+
+pub fn write_json(
+    path: &str,
+    records: &[HistoryRecord],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    std::fs::write(path, serde_json::to_string_pretty(records)?)?;
+    Ok(())
+}
+
+/// One row per record and token, with the record's fee and contract actions
+/// repeated on each of its rows. A record that moved no tokens (only paid a
+/// fee) gets a single `dust` row.
+pub fn write_csv(
+    path: &str,
+    records: &[HistoryRecord],
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let mut csv = String::from(
+        "block,timestamp_ms,extrinsic_hash,kind,token,received,spent,change,net,fee,contract_actions\n",
+    );
+    for record in records {
+        let mut net = record.net();
+        if net.is_empty() {
+            net.insert(tokens::token_type_hex(&TokenType::Dust), 0);
+        }
+        for (token, net) in net {
+            let amount = |map: &BTreeMap<String, u128>| map.get(&token).copied().unwrap_or(0);
+            csv.push_str(&format!(
+                "{},{},{},{},{token},{},{},{},{net},{},{}\n",
+                record.block_number,
+                record.timestamp_ms,
+                record.extrinsic_hash,
+                record.kind,
+                amount(&record.received),
+                amount(&record.spent),
+                amount(&record.change),
+                record.fee,
+                record.contract_actions.join(";"),
+            ));
+        }
+    }
+    std::fs::write(path, csv)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> HistoryRecord {
+        HistoryRecord {
+            block_number: 7,
+            timestamp_ms: 1_000,
+            extrinsic_hash: "0xab".to_string(),
+            kind: "send".to_string(),
+            received: BTreeMap::from([("b".to_string(), 5)]),
+            spent: BTreeMap::from([("a".to_string(), 100)]),
+            change: BTreeMap::from([("a".to_string(), 40)]),
+            fee: 3,
+            contract_actions: vec!["call 00:post".to_string(), "call 00:takeDown".to_string()],
+        }
+    }
+
+    fn csv(records: &[HistoryRecord], name: &str) -> Vec<String> {
+        let path =
+            std::env::temp_dir().join(format!("history-test-{}-{name}.csv", std::process::id()));
+        write_csv(&path.to_string_lossy(), records).unwrap();
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn net_is_received_plus_change_minus_spent() {
+        assert_eq!(
+            record().net(),
+            BTreeMap::from([("a".to_string(), -60), ("b".to_string(), 5)])
+        );
+    }
+
+    #[test]
+    fn csv_has_a_row_per_token() {
+        let rows = csv(&[record()], "tokens");
+        assert_eq!(
+            rows,
+            [
+                "block,timestamp_ms,extrinsic_hash,kind,token,received,spent,change,net,fee,contract_actions",
+                "7,1000,0xab,send,a,0,100,40,-60,3,call 00:post;call 00:takeDown",
+                "7,1000,0xab,send,b,5,0,0,5,3,call 00:post;call 00:takeDown",
+            ]
+        );
+    }
+
+    #[test]
+    fn fee_only_records_get_a_dust_row() {
+        let fee_only = HistoryRecord {
+            kind: "fee".to_string(),
+            fee: 9,
+            ..HistoryRecord::default()
+        };
+        let rows = csv(&[fee_only], "fee");
+        let dust = tokens::token_type_hex(&TokenType::Dust);
+        assert_eq!(rows[1], format!("0,0,,fee,{dust},0,0,0,0,9,"));
+    }
+}
@@ -0,0 +1,342 @@
+//! # Local block index
+//!
+//! A SQLite copy of what `chain::fetch_block` decodes, so historical lookups
+//! don't have to go back to the node:
+//!
+//! - `blocks`: number, hash, parent hash and timestamp
+//! - `transactions`: every Midnight transaction, standard or system, with
+//!   its raw bytes; a standard one that fails to decode is kept without its
+//!   hash and contract actions
+//! - `contract_actions`: contract deploys, calls and maintenance updates,
+//!   from the standard transactions' intents
+//! - `events`: every event of the block, with the extrinsic it belongs to;
+//!   the Midnight pallet's contract events also carry their contract address
+//!   and transaction hash
+//!
+//! Each block is written in one SQL transaction, so an interrupted indexer
+//! resumes from the last complete block.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+use rusqlite::{params, Connection, OptionalExtension};
+use subxt::events::Phase;
+
+use crate::chain::{self, FetchedBlock, RawTransaction};
+use crate::midnight::api::midnight::events::{
+    ClaimRewards, ContractCall, ContractDeploy, ContractMaintain, TxApplied, TxPartialSuccess,
+};
+
+pub const DEFAULT_DB_PATH: &str = "midnight-index.sqlite";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS blocks (
+    number       INTEGER PRIMARY KEY,
+    hash         TEXT NOT NULL UNIQUE,
+    parent_hash  TEXT NOT NULL,
+    timestamp_ms INTEGER
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    block_number    INTEGER NOT NULL REFERENCES blocks(number),
+    extrinsic_index INTEGER NOT NULL,
+    extrinsic_hash  TEXT NOT NULL,
+    kind            TEXT NOT NULL,
+    tx_hash         TEXT,
+    size            INTEGER NOT NULL,
+    raw             BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transactions_tx_hash ON transactions(tx_hash);
+CREATE INDEX IF NOT EXISTS transactions_block ON transactions(block_number);
+CREATE TABLE IF NOT EXISTS contract_actions (
+    block_number     INTEGER NOT NULL REFERENCES blocks(number),
+    extrinsic_index  INTEGER NOT NULL,
+    tx_hash          TEXT NOT NULL,
+    contract_address TEXT NOT NULL,
+    action           TEXT NOT NULL,
+    entry_point      TEXT
+);
+CREATE INDEX IF NOT EXISTS contract_actions_address ON contract_actions(contract_address);
+CREATE TABLE IF NOT EXISTS events (
+    block_number     INTEGER NOT NULL REFERENCES blocks(number),
+    event_index      INTEGER NOT NULL,
+    extrinsic_index  INTEGER,
+    pallet           TEXT NOT NULL,
+    variant          TEXT NOT NULL,
+    tx_hash          TEXT,
+    contract_address TEXT,
+    data             BLOB NOT NULL
+);
+CREATE INDEX IF NOT EXISTS events_kind ON events(pallet, variant);
+CREATE INDEX IF NOT EXISTS events_tx_hash ON events(tx_hash);
+";
+
+pub struct Index {
+    conn: Connection,
+}
+
+impl Index {
+    /// Opens (or creates) the index at `path`.
+    pub fn open(path: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { conn })
+    }
+
+    pub fn conn(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// The highest block indexed so far.
+    pub fn last_block(&self) -> Result<Option<u64>, Box<dyn std::error::Error + Send + Sync>> {
+        let last: Option<i64> =
+            self.conn
+                .query_row("SELECT MAX(number) FROM blocks", [], |row| row.get(0))?;
+        Ok(last.map(|n| n as u64))
+    }
+
+    /// The hash stored for block `number`.
+    pub fn block_hash(
+        &self,
+        number: u64,
+    ) -> Result<Option<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let hash = self
+            .conn
+            .query_row(
+                "SELECT hash FROM blocks WHERE number = ?1",
+                [number as i64],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(hash)
+    }
+
+    /// Writes `block` with its transactions, contract actions and events.
+    pub fn insert_block(
+        &mut self,
+        block: &FetchedBlock,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let db = self.conn.transaction()?;
+
+        db.execute(
+            "INSERT INTO blocks (number, hash, parent_hash, timestamp_ms) VALUES (?1, ?2, ?3, ?4)",
+            params![
+                block.number as i64,
+                hex::encode(block.hash.0),
+                hex::encode(block.parent_hash.0),
+                block.timestamp_ms.map(|t| t as i64),
+            ],
+        )?;
+
+        for block_tx in &block.transactions {
+            let (kind, bytes) = match &block_tx.raw {
+                RawTransaction::Standard(bytes) => ("standard", bytes),
+                RawTransaction::System(bytes) => ("system", bytes),
+            };
+            let tx_hash = match &block_tx.raw {
+                RawTransaction::Standard(bytes) => 'standard: {
+                    let tx = match deserialize::<FinalizedTransaction<DefaultDB>, _>(
+                        &mut bytes.as_slice(),
+                    ) {
+                        Ok(tx) => tx,
+                        Err(e) => {
+                            eprintln!(
+                                "  ⚠ Block {} extrinsic {}: undecodable transaction: {e}",
+                                block.number, block_tx.extrinsic_index
+                            );
+                            break 'standard None;
+                        }
+                    };
+                    let tx_hash = hex::encode(tx.transaction_hash().0 .0);
+                    for (address, action, entry_point) in contract_actions(&tx) {
+                        db.execute(
+                            "INSERT INTO contract_actions
+                                (block_number, extrinsic_index, tx_hash,
+                                 contract_address, action, entry_point)
+                             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                            params![
+                                block.number as i64,
+                                block_tx.extrinsic_index,
+                                tx_hash,
+                                address,
+                                action,
+                                entry_point,
+                            ],
+                        )?;
+                    }
+                    Some(tx_hash)
+                }
+                RawTransaction::System(_) => None,
+            };
+            db.execute(
+                "INSERT INTO transactions
+                    (block_number, extrinsic_index, extrinsic_hash, kind, tx_hash, size, raw)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    block.number as i64,
+                    block_tx.extrinsic_index,
+                    hex::encode(block_tx.extrinsic_hash.0),
+                    kind,
+                    tx_hash,
+                    bytes.len() as i64,
+                    bytes,
+                ],
+            )?;
+        }
+
+        for ev in block.events.iter() {
+            let ev = ev?;
+            let extrinsic_index = match ev.phase() {
+                Phase::ApplyExtrinsic(i) => Some(i),
+                _ => None,
+            };
+            let (tx_hash, contract_address) = midnight_event_refs(&ev);
+            db.execute(
+                "INSERT INTO events
+                    (block_number, event_index, extrinsic_index, pallet, variant,
+                     tx_hash, contract_address, data)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    block.number as i64,
+                    ev.index(),
+                    extrinsic_index,
+                    ev.pallet_name(),
+                    ev.variant_name(),
+                    tx_hash,
+                    contract_address,
+                    ev.field_bytes(),
+                ],
+            )?;
+        }
+
+        db.commit()?;
+        Ok(())
+    }
+}
+
+/// `(address, action, entry point)` for each contract action of `tx`.
+fn contract_actions(
+    tx: &FinalizedTransaction<DefaultDB>,
+) -> Vec<(String, &'static str, Option<String>)> {
+    let Transaction::Standard(stx) = tx else {
+        return Vec::new();
+    };
+    let mut actions = Vec::new();
+    for (_, intent) in stx.intents.iter() {
+        for action in intent.actions.iter() {
+            actions.push(match &action {
+                ContractAction::Call(call) => (
+                    hex::encode(call.address.0 .0),
+                    "call",
+                    Some(String::from_utf8_lossy(&call.entry_point.0).into_owned()),
+                ),
+                ContractAction::Deploy(deploy) => {
+                    (hex::encode(deploy.address().0 .0), "deploy", None)
+                }
+                ContractAction::Maintain(update) => {
+                    (hex::encode(update.address.0 .0), "maintain", None)
+                }
+            });
+        }
+    }
+    actions
+}
+
+/// The transaction hash and contract address carried by a Midnight pallet
+/// event, where it has them.
+fn midnight_event_refs(
+    ev: &subxt::events::EventDetails<subxt::PolkadotConfig>,
+) -> (Option<String>, Option<String>) {
+    let address = |bytes: &[u8]| {
+        chain::decode_event_contract_address(bytes).map(|address| hex::encode(address.0 .0))
+    };
+    if let Ok(Some(e)) = ev.as_event::<ContractDeploy>() {
+        return (
+            Some(hex::encode(e.0.tx_hash)),
+            address(&e.0.contract_address),
+        );
+    }
+    if let Ok(Some(e)) = ev.as_event::<ContractCall>() {
+        return (
+            Some(hex::encode(e.0.tx_hash)),
+            address(&e.0.contract_address),
+        );
+    }
+    if let Ok(Some(e)) = ev.as_event::<ContractMaintain>() {
+        return (
+            Some(hex::encode(e.0.tx_hash)),
+            address(&e.0.contract_address),
+        );
+    }
+    if let Ok(Some(e)) = ev.as_event::<TxApplied>() {
+        return (Some(hex::encode(e.0.tx_hash)), None);
+    }
+    if let Ok(Some(e)) = ev.as_event::<TxPartialSuccess>() {
+        return (Some(hex::encode(e.0.tx_hash)), None);
+    }
+    if let Ok(Some(e)) = ev.as_event::<ClaimRewards>() {
+        return (Some(hex::encode(e.0.tx_hash)), None);
+    }
+    (None, None)
+}
+
+/// Hashes and addresses are stored as lowercase hex without `0x`; this
+/// brings user input to the same form.
+pub fn normalize_hex(input: &str) -> String {
+    input.trim().trim_start_matches("0x").to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_with_blocks(numbers: &[u64]) -> Index {
+        let index = Index::open(":memory:").unwrap();
+        for number in numbers {
+            index
+                .conn()
+                .execute(
+                    "INSERT INTO blocks (number, hash, parent_hash) VALUES (?1, ?2, '')",
+                    params![*number as i64, format!("{number:064x}")],
+                )
+                .unwrap();
+        }
+        index
+    }
+
+    #[test]
+    fn an_empty_index_has_no_last_block() {
+        let index = index_with_blocks(&[]);
+        assert_eq!(index.last_block().unwrap(), None);
+        assert_eq!(index.block_hash(0).unwrap(), None);
+    }
+
+    #[test]
+    fn the_last_block_is_the_highest_number() {
+        let index = index_with_blocks(&[0, 2, 1]);
+        assert_eq!(index.last_block().unwrap(), Some(2));
+        assert_eq!(index.block_hash(1).unwrap(), Some(format!("{:064x}", 1)));
+    }
+
+    #[test]
+    fn opening_twice_keeps_the_schema() {
+        let path = std::env::temp_dir().join(format!("index-test-{}.sqlite", std::process::id()));
+        let path = path.to_string_lossy();
+        let _ = std::fs::remove_file(path.as_ref());
+        Index::open(&path)
+            .unwrap()
+            .conn()
+            .execute(
+                "INSERT INTO blocks (number, hash, parent_hash) VALUES (5, 'aa', '')",
+                [],
+            )
+            .unwrap();
+        assert_eq!(Index::open(&path).unwrap().last_block().unwrap(), Some(5));
+    }
+
+    #[test]
+    fn hex_input_is_normalized() {
+        assert_eq!(normalize_hex(" 0xABcd "), "abcd");
+        assert_eq!(normalize_hex("abcd"), "abcd");
+    }
+}
@@ -0,0 +1,412 @@
+//! # Proving key and parameter cache
+//!
+//! Everything proving needs lives under one cache directory (default
+//! `.midnight-cache`, overridden by `MIDNIGHT_KEY_CACHE`):
+//!
+//! ```text
+//! <cache>/params/                   public params and DUST keys (MIDNIGHT_PP)
+//! <cache>/contracts/<name>/         copies of a contract's keys/ and zkir/
+//! <cache>/contracts/<name>/SHA256SUMS
+//! ```
+//!
+//! `key_cache prefetch` fills the cache while online; afterwards proving works
+//! offline. Contract keys are checked against `SHA256SUMS` whenever they are
+//! resolved, and the ledger's data provider checks params and DUST keys
+//! against its own expected hashes.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_CACHE_DIR: &str = ".midnight-cache";
+pub const CACHE_DIR_ENV: &str = "MIDNIGHT_KEY_CACHE";
+
+/// Where the ledger's `MidnightDataProvider` keeps params and DUST keys.
+const PARAMS_DIR_ENV: &str = "MIDNIGHT_PP";
+
+const CHECKSUMS_FILE: &str = "SHA256SUMS";
+
+#[derive(Clone)]
+pub struct KeyCache {
+    dir: PathBuf,
+}
+
+impl KeyCache {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(std::env::var(CACHE_DIR_ENV).unwrap_or_else(|_| DEFAULT_CACHE_DIR.to_string()))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn params_dir(&self) -> PathBuf {
+        self.dir.join("params")
+    }
+
+    pub fn contract_dir(&self, name: &str) -> PathBuf {
+        self.dir.join("contracts").join(name)
+    }
+
+    /// Points the ledger's data provider at this cache.
+    ///
+    /// Sets `MIDNIGHT_PP`, so it must run before the tokio runtime starts
+    /// (setting the environment while other threads may read it is unsound)
+    /// and before anything touches `PUBLIC_PARAMS`.
+    pub fn install(&self) {
+        std::env::set_var(PARAMS_DIR_ENV, self.params_dir());
+    }
+
+    pub fn data_provider(
+        &self,
+        fetch_mode: FetchMode,
+    ) -> Result<MidnightDataProvider, Box<dyn std::error::Error + Send + Sync>> {
+        if std::env::var_os(PARAMS_DIR_ENV) != Some(self.params_dir().into_os_string()) {
+            return Err(format!(
+                "{PARAMS_DIR_ENV} does not point at {}; KeyCache::install must run first",
+                self.params_dir().display()
+            )
+            .into());
+        }
+        Ok(MidnightDataProvider::new(
+            fetch_mode,
+            OutputMode::Log,
+            DUST_EXPECTED_FILES.to_owned(),
+        )?)
+    }
+
+    // ── Prefetch ─────────────────────────────────────────────────────────
+
+    /// Downloads public params up to `max_k` and every DUST key, verifying
+    /// them against the ledger's expected hashes.
+    pub async fn prefetch_params(
+        &self,
+        max_k: u8,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let provider = self.data_provider(FetchMode::Synchronous)?;
+        for k in 10..=max_k {
+            provider.fetch_k(k).await?;
+            println!("  ✓ params k={k}");
+        }
+        for (name, _hash, _description) in DUST_EXPECTED_FILES.iter() {
+            provider.fetch(name).await?;
+            println!("  ✓ {name}");
+        }
+        Ok(())
+    }
+
+    /// Copies a compiled contract's `keys/` and `zkir/` into the cache and
+    /// records their checksums.
+    ///
+    /// When `source_dir` ships a `SHA256SUMS` (as `static/bboard` does) every
+    /// file is checked against it first and nothing is copied on a mismatch;
+    /// otherwise the digests recorded are just those of the files as read.
+    pub fn import_contract(
+        &self,
+        name: &str,
+        source_dir: &Path,
+    ) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+        let known = if source_dir.join(CHECKSUMS_FILE).is_file() {
+            Some(read_checksums(source_dir)?)
+        } else {
+            eprintln!(
+                "  ⚠ {} has no {CHECKSUMS_FILE}; {name} is imported unverified",
+                source_dir.display()
+            );
+            None
+        };
+
+        let mut files = Vec::new();
+        for sub in ["keys", "zkir"] {
+            let from = source_dir.join(sub);
+            if !from.is_dir() {
+                continue;
+            }
+            for entry in std::fs::read_dir(&from)? {
+                let path = entry?.path();
+                if !path.is_file() {
+                    continue;
+                }
+                let file_name = path.file_name().unwrap().to_string_lossy().to_string();
+                let file = format!("{sub}/{file_name}");
+                let bytes = std::fs::read(&path)?;
+                let sum = sha256_hex(&bytes);
+                if let Some(known) = &known {
+                    match known.get(&file) {
+                        Some(expected) if *expected == sum => {}
+                        Some(_) => {
+                            return Err(format!("Checksum mismatch for {}", path.display()).into())
+                        }
+                        None => {
+                            return Err(format!(
+                                "{} is not listed in {CHECKSUMS_FILE}",
+                                path.display()
+                            )
+                            .into())
+                        }
+                    }
+                }
+                files.push((file, sum, bytes));
+            }
+        }
+
+        let mut checksums = BTreeMap::new();
+        for (file, sum, bytes) in files {
+            let to = self.contract_dir(name).join(&file);
+            if let Some(dir) = to.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::write(to, bytes)?;
+            checksums.insert(file, sum);
+        }
+
+        let sums: String = checksums
+            .iter()
+            .map(|(file, sum)| format!("{sum}  {file}\n"))
+            .collect();
+        std::fs::write(self.contract_dir(name).join(CHECKSUMS_FILE), sums)?;
+        Ok(checksums.len())
+    }
+
+    // ── Verification ─────────────────────────────────────────────────────
+
+    /// Checks every cached contract file against its recorded checksum,
+    /// returning the files that do not match.
+    pub fn verify_contract(
+        &self,
+        name: &str,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error + Send + Sync>> {
+        let dir = self.contract_dir(name);
+        let checksums = read_checksums(&dir)?;
+        let mut mismatched = Vec::new();
+        for (file, expected) in &checksums {
+            match std::fs::read(dir.join(file)) {
+                Ok(bytes) if sha256_hex(&bytes) == *expected => {}
+                _ => mismatched.push(file.clone()),
+            }
+        }
+        Ok(mismatched)
+    }
+
+    pub fn cached_contracts(&self) -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(self.dir.join("contracts")) else {
+            return Vec::new();
+        };
+        let mut names: Vec<String> = entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        names
+    }
+
+    // ── Resolution ───────────────────────────────────────────────────────
+
+    /// Loads the key material for circuit `circuit` of contract `name`.
+    ///
+    /// Reads from the cache when the contract has been imported, otherwise
+    /// straight from `source_dir`, verifying checksums when the directory has
+    /// a `SHA256SUMS`.
+    pub fn contract_key_material(
+        &self,
+        name: &str,
+        source_dir: &Path,
+        circuit: &str,
+    ) -> std::io::Result<Option<ProvingKeyMaterial>> {
+        let source = self.contract_source(name, source_dir)?;
+        let (Some(prover_key), Some(verifier_key), Some(ir_source)) = (
+            source.read(&format!("keys/{circuit}.prover"))?,
+            source.read(&format!("keys/{circuit}.verifier"))?,
+            source.read(&format!("zkir/{circuit}.bzkir"))?,
+        ) else {
+            return Ok(None);
+        };
+
+        Ok(Some(ProvingKeyMaterial {
+            prover_key,
+            verifier_key,
+            ir_source,
+        }))
+    }
+
+    /// The serialized verifier key of circuit `circuit` of contract `name`,
+    /// found and verified like [`KeyCache::contract_key_material`].
+    pub fn contract_verifier_key(
+        &self,
+        name: &str,
+        source_dir: &Path,
+        circuit: &str,
+    ) -> std::io::Result<Option<Vec<u8>>> {
+        self.contract_source(name, source_dir)?
+            .read(&format!("keys/{circuit}.verifier"))
+    }
+
+    fn contract_source(&self, name: &str, source_dir: &Path) -> std::io::Result<ContractSource> {
+        let cached = self.contract_dir(name);
+        let dir = if cached.join(CHECKSUMS_FILE).is_file() {
+            cached
+        } else {
+            source_dir.to_path_buf()
+        };
+        let checksums = if dir.join(CHECKSUMS_FILE).is_file() {
+            Some(read_checksums(&dir).map_err(std::io::Error::other)?)
+        } else {
+            None
+        };
+        Ok(ContractSource { dir, checksums })
+    }
+
+    /// A `Resolver` for contract `name`: params and DUST keys from the cache,
+    /// circuit keys via [`KeyCache::contract_key_material`].
+    pub fn contract_resolver(
+        &self,
+        name: &str,
+        source_dir: &str,
+    ) -> Result<Resolver, Box<dyn std::error::Error + Send + Sync>> {
+        let cache = self.clone();
+        let name = name.to_string();
+        let source_dir = PathBuf::from(source_dir);
+        Ok(Resolver::new(
+            PUBLIC_PARAMS.clone(),
+            DustResolver(self.data_provider(FetchMode::OnDemand)?),
+            Box::new(move |key_location| {
+                let result = cache.contract_key_material(&name, &source_dir, &key_location.0);
+                Box::pin(std::future::ready(result))
+            }),
+        ))
+    }
+}
+
+/// Where a contract's key files are read from.
+struct ContractSource {
+    dir: PathBuf,
+    /// Contents of the directory's `SHA256SUMS`, if it has one.
+    checksums: Option<BTreeMap<String, String>>,
+}
+
+impl ContractSource {
+    /// Reads `file`, or `None` if it doesn't exist. With checksums, a file
+    /// that isn't listed or doesn't match is an error.
+    fn read(&self, file: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let path = self.dir.join(file);
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if let Some(checksums) = &self.checksums {
+            let expected = checksums.get(file).ok_or_else(|| {
+                std::io::Error::other(format!(
+                    "{} is not listed in {CHECKSUMS_FILE}; run `key_cache prefetch` again",
+                    path.display()
+                ))
+            })?;
+            if sha256_hex(&bytes) != *expected {
+                return Err(std::io::Error::other(format!(
+                    "Checksum mismatch for {}; run `key_cache prefetch` again",
+                    path.display()
+                )));
+            }
+        }
+        Ok(Some(bytes))
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn read_checksums(
+    dir: &Path,
+) -> Result<BTreeMap<String, String>, Box<dyn std::error::Error + Send + Sync>> {
+    let contents = std::fs::read_to_string(dir.join(CHECKSUMS_FILE))?;
+    contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (sum, file) = line
+                .split_once("  ")
+                .ok_or_else(|| format!("Malformed {CHECKSUMS_FILE} line: {line}"))?;
+            Ok((file.to_string(), sum.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A contract source directory under the temp dir holding `files`.
+    fn source(name: &str, files: &[(&str, &[u8])]) -> ContractSource {
+        let dir = std::env::temp_dir().join(format!("keys-test-{}-{name}", std::process::id()));
+        for (file, bytes) in files {
+            let path = dir.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, bytes).unwrap();
+        }
+        ContractSource {
+            dir,
+            checksums: None,
+        }
+    }
+
+    fn listing(files: &[(&str, &[u8])]) -> BTreeMap<String, String> {
+        files
+            .iter()
+            .map(|(file, bytes)| (file.to_string(), sha256_hex(bytes)))
+            .collect()
+    }
+
+    #[test]
+    fn listed_files_are_read() {
+        let files: &[(&str, &[u8])] = &[("keys/post.verifier", b"key")];
+        let source = ContractSource {
+            checksums: Some(listing(files)),
+            ..source("listed", files)
+        };
+        assert_eq!(
+            source.read("keys/post.verifier").unwrap(),
+            Some(b"key".to_vec())
+        );
+        assert_eq!(source.read("keys/missing.verifier").unwrap(), None);
+    }
+
+    #[test]
+    fn unlisted_files_are_rejected() {
+        let source = ContractSource {
+            checksums: Some(listing(&[("keys/post.verifier", b"key")])),
+            ..source("unlisted", &[("keys/takeDown.verifier", b"key")])
+        };
+        let err = source.read("keys/takeDown.verifier").unwrap_err();
+        assert!(err.to_string().contains("not listed"), "{err}");
+    }
+
+    #[test]
+    fn mismatched_files_are_rejected() {
+        let source = ContractSource {
+            checksums: Some(listing(&[("keys/post.verifier", b"key")])),
+            ..source("mismatched", &[("keys/post.verifier", b"tampered")])
+        };
+        let err = source.read("keys/post.verifier").unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+    }
+
+    #[test]
+    fn without_checksums_files_are_read_unchecked() {
+        let source = source("unchecked", &[("keys/post.verifier", b"key")]);
+        assert_eq!(
+            source.read("keys/post.verifier").unwrap(),
+            Some(b"key".to_vec())
+        );
+    }
+}
@@ -9,22 +9,31 @@
 //! 3. Build OfferInfo (inputs + outputs)
 //! 4. Prove via StandardTrasactionInfo
 //! 5. Serialize and submit
+//!
+//! Pass `--proof-server <url>` to prove on a remote proof server instead of
+//! in-process, or `--mock-proofs` to skip proving against a dev node that
+//! accepts mock proofs (see `prover.rs`). `--rng-seed <seed>` makes the
+//! built transaction reproducible (see `rng.rs`), and `--build-only <file>`
+//! stops before proving to continue offline (see `txfile.rs`).
+//!
+//! After replay the local zswap root is checked against the node's;
+//! `--verify-root-every <n>` also checks every n blocks. `--bootstrap-from
+//! <block>` skips replaying older blocks by seeding the zswap state from the
+//! node (see `chain::bootstrap`).
+//!
+//! Proving params come from the key cache; run `key_cache prefetch` first to
+//! work offline (see `keys.rs`).
 
+mod chain;
+mod cli;
+mod keys;
 mod midnight;
+mod prover;
+mod rng;
+mod txfile;
 
 use midnight_node_ledger_helpers::*;
 use std::sync::Arc;
-use subxt::backend::legacy::{rpc_methods::NumberOrHex, LegacyRpcMethods};
-use subxt::backend::rpc::RpcClient;
-
-// Use our local subxt-generated types for decoding extrinsics and events.
-use midnight::api::runtime_types::midnight_node_runtime::RuntimeCall;
-use midnight::api::runtime_types::pallet_midnight::pallet::Call as MidnightCall;
-use midnight::api::runtime_types::pallet_midnight_system::pallet::Call as MidnightSystemCall;
-use midnight::api::runtime_types::pallet_timestamp::pallet::Call as TimestampCall;
-
-// The event wrapper type (implements StaticEvent, unlike the raw runtime type)
-use midnight::api::midnight_system::events::SystemTransactionApplied;
 
 // ─── Configuration ───────────────────────────────────────────────────────────
 
@@ -43,11 +52,17 @@ const TOKEN_TYPE_HEX: &str =
 
 // ─── Main ────────────────────────────────────────────────────────────────────
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    // Before the runtime starts any threads; see `KeyCache::install`
+    keys::KeyCache::from_env().install();
+    tokio::runtime::Runtime::new()?.block_on(run())
+}
+
+async fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("=== Midnight Transaction Builder (Toolkit-style) ===\n");
 
     // ── Step 1: Parse config ─────────────────────────────────────────────
+    let args = cli::Args::from_env(prover::SWITCHES);
     let seed: WalletSeed = WALLET_SEED_HEX
         .parse()
         .expect("Invalid wallet seed hex");
@@ -63,20 +78,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("  Send amount: {SEND_AMOUNT}");
 
     // ── Step 2: Connect to node ──────────────────────────────────────────
-    println!("\nConnecting to {NODE_URL}...");
-    let api = subxt::OnlineClient::<subxt::PolkadotConfig>::from_url(NODE_URL).await?;
-    let rpc_client = RpcClient::from_insecure_url(NODE_URL).await?;
-    let rpc = LegacyRpcMethods::<subxt::PolkadotConfig>::new(rpc_client);
-    println!("✓ Connected");
+    let (api, rpc) = chain::connect(NODE_URL).await?;
 
     // ── Step 3: Get network_id from the node ─────────────────────────────
-    let network_id: String = api
-        .runtime_api()
-        .at_latest()
-        .await?
-        .call(midnight::api::apis().midnight_runtime_api().get_network_id())
-        .await?;
+    let network_id = chain::fetch_network_id(&api).await?;
     println!("✓ Network ID: {network_id}");
+    chain::ensure_ledger_compatible(&api).await?;
 
     // ── Step 4: Setup LedgerContext with our wallet ──────────────────────
     let wallet_seeds = vec![seed];
@@ -84,109 +91,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let context = Arc::new(context);
 
     // ── Step 5: Fetch and replay all finalized blocks ────────────────────
-    //
-    // This mirrors what the toolkit's fetcher does:
-    //   For each block → decode extrinsics → extract timestamp + midnight txs
-    //   → tagged_deserialize → context.update_from_block()
-    //
-    println!("\nFetching and replaying blocks...");
-    let finalized_height = api.blocks().at_latest().await?.number() as u64;
-    println!("  Finalized height: {finalized_height}");
-
-    for block_num in 0..=finalized_height {
-        // Get block hash
-        let block_hash = rpc
-            .chain_get_block_hash(Some(NumberOrHex::Number(block_num)))
-            .await?
-            .ok_or_else(|| format!("Block hash missing for block {block_num}"))?;
-
-        // Fetch block
-        let block = api.blocks().at(block_hash).await?;
-        let extrinsics = block.extrinsics().await?;
-        let parent_hash = block.header().parent_hash;
-
-        // Extract timestamp and midnight transactions from this block
-        let mut timestamp_ms: Option<u64> = None;
-        let mut txs: Vec<SerdeTransaction<Signature, ProofMarker, DefaultDB>> = Vec::new();
-
-        // Also fetch events for non-genesis blocks (system txs come from events)
-        let events = block.events().await?;
-
-        for ext in extrinsics.iter() {
-            // Decode the extrinsic as our RuntimeCall
-            let Ok(call) = ext.as_root_extrinsic::<RuntimeCall>() else {
-                continue;
-            };
-
-            match call {
-                // Extract timestamp
-                RuntimeCall::Timestamp(TimestampCall::set { now }) => {
-                    timestamp_ms = Some(now);
-                }
-                // Extract midnight transaction bytes and deserialize
-                RuntimeCall::Midnight(MidnightCall::send_mn_transaction { midnight_tx }) => {
-                    match deserialize::<FinalizedTransaction<DefaultDB>, _>(&mut midnight_tx.as_slice()) {
-                        Ok(tx) => txs.push(SerdeTransaction::Midnight(tx)),
-                        Err(e) => {
-                            eprintln!("  ⚠ Block {block_num}: failed to deserialize mn tx: {e}");
-                        }
-                    }
-                }
-                // Genesis block: extract system transactions directly from extrinsics
-                // (genesis has no events since events are emitted during block execution)
-                RuntimeCall::MidnightSystem(
-                    MidnightSystemCall::send_mn_system_transaction { midnight_system_tx },
-                ) if block_num == 0 => {
-                    match deserialize::<SystemTransaction, _>(&mut midnight_system_tx.as_slice()) {
-                        Ok(tx) => txs.push(SerdeTransaction::System(tx)),
-                        Err(e) => {
-                            eprintln!("  ⚠ Block {block_num}: failed to deserialize system tx: {e}");
-                        }
-                    }
-                }
-                _ => {}
-            }
-
-            // Non-genesis blocks: extract system transactions from events.
-            // This handles system txs regardless of how they were triggered
-            // (direct calls, governance-wrapped, cNight observation, etc.)
-            if block_num > 0 {
-                let ext_events = subxt::blocks::ExtrinsicEvents::new(
-                    ext.hash(),
-                    ext.index(),
-                    events.clone(),
-                );
-                for ev in ext_events.iter().filter_map(Result::ok) {
-                    if let Ok(Some(event)) = ev.as_event::<SystemTransactionApplied>() {
-                        let bytes = event.0.serialized_system_transaction;
-                        match deserialize::<SystemTransaction, _>(&mut bytes.as_slice()) {
-                            Ok(tx) => txs.push(SerdeTransaction::System(tx)),
-                            Err(e) => {
-                                eprintln!("  ⚠ Block {block_num}: failed to deserialize system tx from event: {e}");
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // Build BlockContext (same as toolkit's compute_task.rs)
-        let timestamp_ms = timestamp_ms.expect("Block has no timestamp extrinsic");
-        let block_context = BlockContext {
-            tblock: Timestamp::from_secs(timestamp_ms / 1000),
-            tblock_err: 30,
-            parent_block_hash: HashOutput(parent_hash.0),
-        };
-
-        // Replay into LedgerContext
-        context.update_from_block(txs, block_context, None);
-
-        // Progress indicator
-        if block_num % 100 == 0 || block_num == finalized_height {
-            print!("\r  Replayed block {block_num}/{finalized_height}");
-        }
-    }
-    println!("\n✓ All blocks replayed");
+    chain::sync(&api, &rpc, &context, &chain::SyncOptions::from_args(&args)?).await?;
 
     // Print wallet state
     let wallet = context.wallet_from_seed(seed);
@@ -243,12 +148,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     };
 
     // ── Step 7: Build StandardTrasactionInfo and prove ────────────────────
-    let prover: Arc<dyn ProofProvider<DefaultDB>> = Arc::new(LocalProofServer::new());
+    let prover = prover::from_args(&args, &network_id).await?;
 
     let mut tx_info = StandardTrasactionInfo::new_from_context(
         context.clone(),
-        prover,
-        None, // random RNG seed
+        prover.provider.clone(),
+        rng::seed_from_args(&args), // random unless --rng-seed is given
     );
 
     // Set the offer (guaranteed = included in every valid block)
@@ -261,7 +166,11 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     tx_info.use_mock_proofs_for_fees(true);
 
     println!("\nProving transaction...");
-    let proven_tx = tx_info.prove().await?;
+    let proven_tx = match prover.prove(&mut tx_info).await? {
+        prover::Outcome::Proven(tx) => tx,
+        // Continued by `offline_tx prove` and `offline_tx submit`
+        prover::Outcome::Exported(_) => return Ok(()),
+    };
     println!("✓ Transaction proven");
 
     // ── Step 8: Serialize ────────────────────────────────────────────────
@@ -269,39 +178,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     println!("✓ Serialized ({} bytes)", serialized.len());
 
     // ── Step 9: Submit ───────────────────────────────────────────────────
-    submit_transaction(&api, serialized).await?;
-
-    Ok(())
-}
-
-// ─── Submit via subxt ────────────────────────────────────────────────────────
-
-async fn submit_transaction(
-    api: &subxt::OnlineClient<subxt::PolkadotConfig>,
-    serialized_tx: Vec<u8>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    println!("\n=== Submitting Transaction ===\n");
-
-    let tx_payload = midnight::api::tx()
-        .midnight()
-        .send_mn_transaction(serialized_tx);
-
-    let unsigned = api.tx().create_unsigned(&tx_payload)?;
-    let progress = unsigned.submit_and_watch().await?;
-
-    println!("✓ Transaction submitted!");
-    println!("  Extrinsic hash: 0x{}", hex::encode(progress.extrinsic_hash().0));
-    println!("  Waiting for finalization...\n");
-
-    match progress.wait_for_finalized_success().await {
-        Ok(_events) => {
-            println!("✅ Transaction finalized successfully!");
-        }
-        Err(e) => {
-            println!("⚠️  Transaction failed:");
-            println!("   Error: {e:?}");
-        }
-    }
+    chain::submit_transaction(&api, serialized).await?;
 
     Ok(())
 }
@@ -0,0 +1,285 @@
+//! # Contract Maintenance Updates
+//!
+//! Builds `MaintenanceUpdate`s signed by a contract's maintenance committee:
+//! replacing a verifier key, adding or removing an operation, or rotating the
+//! committee itself.
+//!
+//! Committee members usually hold their keys on different machines, so a
+//! pending update travels as a file (hex of the serialized update). Each
+//! member signs it in turn and whoever holds it once the threshold is reached
+//! submits it.
+
+use midnight_node_ledger_helpers::contract::BuildContractAction;
+use midnight_node_ledger_helpers::*;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use subxt::blocks::ExtrinsicEvents;
+
+use crate::chain;
+use crate::midnight::api::midnight::events::ContractMaintain;
+
+/// A single change requested from the maintenance committee.
+pub enum MaintenanceOp {
+    /// Swap the verifier key of an existing operation.
+    ReplaceVerifierKey { operation: String, key: VerifierKey },
+    /// Register a new operation with its verifier key.
+    AddOperation { operation: String, key: VerifierKey },
+    /// Remove an operation so it can no longer be called.
+    RemoveOperation { operation: String },
+    /// Replace the committee and threshold.
+    RotateCommittee {
+        committee: Vec<VerifyingKey>,
+        threshold: u32,
+    },
+}
+
+impl MaintenanceOp {
+    fn single_updates(
+        &self,
+        state: &ContractState<DefaultDB>,
+    ) -> Result<Vec<SingleUpdate>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match self {
+            MaintenanceOp::ReplaceVerifierKey { operation, key } => vec![
+                SingleUpdate::VerifierKeyRemove(
+                    operation.as_bytes().into(),
+                    operation_version(state, operation)?,
+                ),
+                SingleUpdate::VerifierKeyInsert(
+                    operation.as_bytes().into(),
+                    ContractOperationVersionedVerifierKey::V2(key.clone()),
+                ),
+            ],
+            MaintenanceOp::AddOperation { operation, key } => {
+                vec![SingleUpdate::VerifierKeyInsert(
+                    operation.as_bytes().into(),
+                    ContractOperationVersionedVerifierKey::V2(key.clone()),
+                )]
+            }
+            MaintenanceOp::RemoveOperation { operation } => vec![SingleUpdate::VerifierKeyRemove(
+                operation.as_bytes().into(),
+                operation_version(state, operation)?,
+            )],
+            // The ledger bumps the counter when it applies the update, so the
+            // replacement authority has to carry the bumped value too.
+            MaintenanceOp::RotateCommittee {
+                committee,
+                threshold,
+            } => vec![SingleUpdate::ReplaceAuthority(
+                ContractMaintenanceAuthority {
+                    committee: committee.clone(),
+                    threshold: *threshold,
+                    counter: state
+                        .maintenance_authority
+                        .counter
+                        .checked_add(1)
+                        .ok_or("Maintenance counter overflow")?,
+                },
+            )],
+        })
+    }
+}
+
+/// The version of the verifier key `operation` has on chain. New keys are
+/// always inserted as V2, the version the key files are built for.
+fn operation_version(
+    state: &ContractState<DefaultDB>,
+    operation: &str,
+) -> Result<ContractOperationVersion, Box<dyn std::error::Error + Send + Sync>> {
+    let op = state
+        .operations
+        .get(&operation.as_bytes().into())
+        .ok_or_else(|| format!("Contract has no operation '{operation}'"))?;
+    match op.v2 {
+        Some(_) => Ok(ContractOperationVersion::V2),
+        None => Err(format!("Operation '{operation}' has no verifier key to remove").into()),
+    }
+}
+
+/// Checks `op` against the contract's current state before anyone signs it.
+pub fn validate_op(
+    op: &MaintenanceOp,
+    state: &ContractState<DefaultDB>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let has_operation = |name: &str| state.operations.get(&name.as_bytes().into()).is_some();
+
+    match op {
+        MaintenanceOp::ReplaceVerifierKey { operation, .. }
+        | MaintenanceOp::RemoveOperation { operation } => {
+            if !has_operation(operation) {
+                return Err(format!("Contract has no operation '{operation}'").into());
+            }
+        }
+        MaintenanceOp::AddOperation { operation, .. } => {
+            if has_operation(operation) {
+                return Err(format!("Contract already has operation '{operation}'").into());
+            }
+        }
+        MaintenanceOp::RotateCommittee {
+            committee,
+            threshold,
+        } => {
+            if *threshold == 0 || *threshold as usize > committee.len() {
+                return Err(format!(
+                    "Threshold {threshold} is invalid for a committee of {}",
+                    committee.len()
+                )
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Builds an unsigned update against the contract's current counter.
+pub fn prepare_update(
+    address: ContractAddress,
+    ops: &[MaintenanceOp],
+    state: &ContractState<DefaultDB>,
+) -> Result<MaintenanceUpdate<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
+    let mut updates = Vec::new();
+    for op in ops {
+        updates.extend(op.single_updates(state)?);
+    }
+    Ok(MaintenanceUpdate::new(
+        address,
+        updates,
+        state.maintenance_authority.counter,
+    ))
+}
+
+/// Fails unless `update` was prepared against the contract's current
+/// counter; the ledger rejects it otherwise, and a committee rotation in it
+/// would carry the wrong counter.
+pub fn check_counter(
+    update: &MaintenanceUpdate<DefaultDB>,
+    state: &ContractState<DefaultDB>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if update.counter != state.maintenance_authority.counter {
+        return Err(format!(
+            "Update was prepared for counter {} but the contract is at {}; prepare it again",
+            update.counter, state.maintenance_authority.counter
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Adds the signature of the committee member holding `signing_key`.
+pub fn sign_update(
+    update: MaintenanceUpdate<DefaultDB>,
+    signing_key: &SigningKey,
+    authority: &ContractMaintenanceAuthority,
+    rng: &mut StdRng,
+) -> Result<MaintenanceUpdate<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
+    let verifying_key = signing_key.verifying_key();
+    let index = authority
+        .committee
+        .iter()
+        .position(|member| *member == verifying_key)
+        .ok_or("Signing key is not a member of the maintenance committee")?;
+
+    if update.signatures.iter().any(|sig| sig.0 == index as u32) {
+        return Err(format!("Committee member {index} has already signed").into());
+    }
+
+    let signature = signing_key.sign(rng, &update.data_to_sign());
+    Ok(update.add_signature(index as u32, signature))
+}
+
+pub fn signature_count(update: &MaintenanceUpdate<DefaultDB>) -> usize {
+    update.signatures.iter().count()
+}
+
+// ─── Pending update files ────────────────────────────────────────────────────
+
+pub fn write_update_file(
+    path: &str,
+    update: &MaintenanceUpdate<DefaultDB>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let bytes = serialize(update)?;
+    std::fs::write(path, hex::encode(bytes))?;
+    Ok(())
+}
+
+pub fn read_update_file(
+    path: &str,
+) -> Result<MaintenanceUpdate<DefaultDB>, Box<dyn std::error::Error + Send + Sync>> {
+    let contents = std::fs::read_to_string(path)?;
+    let bytes = hex::decode(contents.trim())?;
+    Ok(deserialize(&mut bytes.as_slice())?)
+}
+
+// ─── Intent action ───────────────────────────────────────────────────────────
+
+/// Adds a fully signed `MaintenanceUpdate` to the intent being built.
+pub struct MaintenanceUpdateAction {
+    pub update: MaintenanceUpdate<DefaultDB>,
+}
+
+#[async_trait]
+impl BuildContractAction<DefaultDB> for MaintenanceUpdateAction {
+    async fn build(
+        &mut self,
+        _rng: &mut StdRng,
+        _context: Arc<LedgerContext<DefaultDB>>,
+        intent: &Intent<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB>,
+    ) -> Intent<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB> {
+        intent.add_maintenance_update(self.update.clone())
+    }
+}
+
+// ─── Events ──────────────────────────────────────────────────────────────────
+
+/// Prints the `ContractMaintain` events emitted for `update`'s contract by a
+/// finalized extrinsic, with the changes `update` applied. Returns how many
+/// there were.
+pub fn print_maintain_events(
+    events: &ExtrinsicEvents<subxt::PolkadotConfig>,
+    update: &MaintenanceUpdate<DefaultDB>,
+) -> usize {
+    let mut found = 0;
+    for ev in events.find::<ContractMaintain>().filter_map(Result::ok) {
+        let Some(address) = chain::decode_event_contract_address(&ev.0.contract_address) else {
+            eprintln!("  ⚠ Undecodable contract address in ContractMaintain event");
+            continue;
+        };
+        if address != update.address {
+            continue;
+        }
+        found += 1;
+        println!("  ContractMaintain:");
+        println!("    Tx hash:          0x{}", hex::encode(ev.0.tx_hash));
+        println!("    Contract address: {}", hex::encode(address.0 .0));
+        println!("    Counter:          {}", update.counter);
+        for single in update.updates.iter() {
+            println!("    {}", describe_update(&single));
+        }
+    }
+    found
+}
+
+fn describe_update(update: &SingleUpdate) -> String {
+    match update {
+        SingleUpdate::ReplaceAuthority(authority) => format!(
+            "Authority ← {}/{} committee, counter {}",
+            authority.threshold,
+            authority.committee.len(),
+            authority.counter
+        ),
+        SingleUpdate::VerifierKeyRemove(operation, version) => format!(
+            "Removed verifier key: {} ({version:?})",
+            String::from_utf8_lossy(&operation.0)
+        ),
+        SingleUpdate::VerifierKeyInsert(operation, key) => {
+            let version = match key {
+                ContractOperationVersionedVerifierKey::V2(_) => ContractOperationVersion::V2,
+            };
+            format!(
+                "Inserted verifier key: {} ({version:?})",
+                String::from_utf8_lossy(&operation.0)
+            )
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! # Deployment manifests and the contract registry
+//!
+//! A JSON record is written after a successful deploy, so the contract's
+//! address and maintenance authority are known without reading them back from
+//! the chain. Every network keeps its deployments in a registry file
+//! (`deployments/<network id>.json`) keyed by name, which lets the other
+//! binaries take a contract name wherever they take an address.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+use crate::chain;
+
+/// Default directory for manifests, relative to the project root.
+pub const DEPLOYMENTS_DIR: &str = "deployments";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeploymentManifest {
+    /// Registry name (e.g. `bboard`).
+    pub contract: String,
+    pub network_id: String,
+    /// Contract address, hex-encoded.
+    pub address: String,
+    /// See [`code_hash`].
+    pub code_hash: String,
+    /// Block the deploy transaction was finalized in.
+    pub deploy_block: u64,
+    /// Extrinsic hash of the deploy transaction, `0x`-prefixed.
+    pub extrinsic_hash: String,
+    /// Hex-encoded verifying keys of the maintenance committee.
+    pub committee: Vec<String>,
+    pub threshold: u32,
+}
+
+impl DeploymentManifest {
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_json(path, self)
+    }
+}
+
+/// SHA-256 over the contract's operations: each entry point name followed by
+/// its serialized `ContractOperation`, in name order. Two deploys of the same
+/// compiled contract share a code hash regardless of their initial data.
+pub fn code_hash(
+    state: &ContractState<DefaultDB>,
+) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    let mut operations = state
+        .operations
+        .iter()
+        .map(|(name, op)| Ok((name.0.clone(), serialize(&*op)?)))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error + Send + Sync>>>()?;
+    operations.sort();
+
+    let mut hasher = Sha256::new();
+    for (name, op) in operations {
+        hasher.update((name.len() as u32).to_le_bytes());
+        hasher.update(&name);
+        hasher.update(&op);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+// ─── Registry ────────────────────────────────────────────────────────────────
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Registry {
+    pub contracts: BTreeMap<String, DeploymentManifest>,
+}
+
+impl Registry {
+    pub fn path(network_id: &str) -> String {
+        format!("{DEPLOYMENTS_DIR}/{network_id}.json")
+    }
+
+    /// Loads the registry for `network_id`; a missing file is an empty registry.
+    pub fn load(network_id: &str) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let path = Self::path(network_id);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => Ok(serde_json::from_str(&contents)
+                .map_err(|e| format!("Corrupt registry {path}: {e}"))?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, network_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        write_json(&Self::path(network_id), self)
+    }
+
+    /// Records a deployment, returning the entry it replaced under that name.
+    pub fn insert(&mut self, manifest: DeploymentManifest) -> Option<DeploymentManifest> {
+        self.contracts.insert(manifest.contract.clone(), manifest)
+    }
+
+    /// Resolves a registered name, or parses `name_or_address` as a hex address.
+    pub fn resolve(
+        &self,
+        name_or_address: &str,
+    ) -> Result<ContractAddress, Box<dyn std::error::Error + Send + Sync>> {
+        match self.contracts.get(name_or_address) {
+            Some(manifest) => chain::parse_contract_address(&manifest.address),
+            None => chain::parse_contract_address(name_or_address).map_err(|_| {
+                format!("'{name_or_address}' is neither a registered contract nor an address")
+                    .into()
+            }),
+        }
+    }
+}
+
+/// Resolves a contract name or address against the node's network registry.
+pub async fn resolve_contract(
+    api: &chain::Api,
+    name_or_address: &str,
+) -> Result<ContractAddress, Box<dyn std::error::Error + Send + Sync>> {
+    let network_id = chain::fetch_network_id(api).await?;
+    Registry::load(&network_id)?.resolve(name_or_address)
+}
+
+fn write_json<T: Serialize>(
+    path: &str,
+    value: &T,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(dir) = std::path::Path::new(path).parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(value)?)?;
+    Ok(())
+}
@@ -0,0 +1,246 @@
+//! # Proof providers
+//!
+//! Proving runs in-process through `LocalProofServer` by default. With
+//! `--proof-server <url>` it is offloaded to a standalone proof server over
+//! HTTP instead, so low-powered machines only build and submit.
+//!
+//! `--mock-proofs` skips proving altogether and attaches mock proofs, the same
+//! ones used for fee estimation. Only a dev node configured to accept mock
+//! proofs takes such transactions; the point is integration tests that run in
+//! seconds. The tests under `tests/` need no node at all: they run the stub
+//! with mock proofs and BBoard's circuits against a local ledger state.
+//!
+//! ## Wire format
+//! `POST <url>/prove-tx` with the tagged-serialized unproven transaction
+//! followed by the tagged-serialized `CostModel`; the response body is the
+//! tagged-serialized proven transaction. `GET <url>/health` answers `200` when
+//! the server is up. `proof_server_stub` implements the same protocol.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::cli;
+use crate::txfile;
+
+/// Switches to pass to `cli::Args::from_env` in binaries that prove.
+pub const SWITCHES: &[&str] = &["mock-proofs"];
+
+pub struct RemoteProofServerConfig {
+    /// Base URL, e.g. `http://localhost:6300`.
+    pub endpoint: String,
+    /// Per-attempt timeout. Proving a large transaction takes minutes.
+    pub timeout: Duration,
+    /// Attempts after the first one, for connection errors and 5xx responses.
+    pub retries: u32,
+    /// Wait before the first retry; doubled for each further one.
+    pub retry_backoff: Duration,
+}
+
+impl RemoteProofServerConfig {
+    pub fn new(endpoint: &str) -> Self {
+        Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            timeout: Duration::from_secs(600),
+            retries: 3,
+            retry_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+pub struct RemoteProofServer {
+    config: RemoteProofServerConfig,
+    client: reqwest::Client,
+}
+
+impl RemoteProofServer {
+    pub fn new(
+        config: RemoteProofServerConfig,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let client = reqwest::Client::builder().timeout(config.timeout).build()?;
+        Ok(Self { config, client })
+    }
+
+    pub async fn check_health(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/health", self.config.endpoint);
+        self.client.get(&url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn post_with_retries(
+        &self,
+        path: &str,
+        body: Vec<u8>,
+    ) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}{path}", self.config.endpoint);
+        let mut backoff = self.config.retry_backoff;
+        let mut attempt = 0;
+
+        loop {
+            let result = self
+                .client
+                .post(&url)
+                .header("content-type", "application/octet-stream")
+                .body(body.clone())
+                .send()
+                .await;
+
+            let retryable = match result {
+                Ok(response) if response.status().is_success() => {
+                    return Ok(response.bytes().await?.to_vec());
+                }
+                Ok(response) if response.status().is_server_error() => {
+                    format!("{url} answered {}", response.status())
+                }
+                Ok(response) => {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    return Err(format!("{url} rejected the request ({status}): {text}").into());
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => format!("{url}: {e}"),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= self.config.retries {
+                return Err(format!("{retryable} (gave up after {} attempts)", attempt + 1).into());
+            }
+            attempt += 1;
+            eprintln!(
+                "  ⚠ Proof server: {retryable}; retry {attempt}/{} in {backoff:?}",
+                self.config.retries
+            );
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+    }
+}
+
+#[async_trait]
+impl<D: DB + Clone> ProofProvider<D> for RemoteProofServer {
+    async fn prove(
+        &self,
+        tx: Transaction<Signature, ProofPreimageMarker, PedersenRandomness, D>,
+        _rng: StdRng,
+        _resolver: &Resolver,
+        cost_model: &CostModel,
+    ) -> Result<Transaction<Signature, ProofMarker, PedersenRandomness, D>, anyhow::Error> {
+        let mut body = serialize(&tx).map_err(anyhow::Error::msg)?;
+        body.extend(serialize(cost_model).map_err(anyhow::Error::msg)?);
+
+        let response = self
+            .post_with_retries("/prove-tx", body)
+            .await
+            .map_err(|e| anyhow::anyhow!("Remote proving failed: {e}"))?;
+
+        deserialize(&mut response.as_slice()).map_err(anyhow::Error::msg)
+    }
+}
+
+// ─── Mock proofs ─────────────────────────────────────────────────────────────
+
+pub struct MockProofServer;
+
+#[async_trait]
+impl<D: DB + Clone> ProofProvider<D> for MockProofServer {
+    async fn prove(
+        &self,
+        tx: Transaction<Signature, ProofPreimageMarker, PedersenRandomness, D>,
+        _rng: StdRng,
+        _resolver: &Resolver,
+        _cost_model: &CostModel,
+    ) -> Result<Transaction<Signature, ProofMarker, PedersenRandomness, D>, anyhow::Error> {
+        tx.mock_prove()
+            .map_err(|e| anyhow::anyhow!("Mock proving failed: {e:?}"))
+    }
+}
+
+// ─── Selection ───────────────────────────────────────────────────────────────
+
+/// What building came to: the proven transaction or, with `--build-only`,
+/// the path of the unproven one.
+pub enum Outcome {
+    Proven(Transaction<Signature, ProofMarker, PedersenRandomness, DefaultDB>),
+    Exported(String),
+}
+
+/// The proof provider chosen by [`from_args`].
+pub struct Prover {
+    pub provider: Arc<dyn ProofProvider<DefaultDB>>,
+    export: Option<Arc<txfile::ExportingProver>>,
+}
+
+impl Prover {
+    fn new(provider: Arc<dyn ProofProvider<DefaultDB>>) -> Self {
+        Self {
+            provider,
+            export: None,
+        }
+    }
+
+    /// Runs the builder's `prove()`, which hands the transaction to
+    /// `provider`. With `--build-only` the exporting prover stops it once the
+    /// unproven transaction is written; that comes back as
+    /// [`Outcome::Exported`], and any other failure as an error.
+    pub async fn prove(
+        &self,
+        tx_info: &mut StandardTrasactionInfo<DefaultDB>,
+    ) -> Result<Outcome, Box<dyn std::error::Error + Send + Sync>> {
+        let result = tx_info.prove().await;
+        if let (Err(_), Some(export)) = (&result, &self.export) {
+            if export.take_written() {
+                return Ok(Outcome::Exported(export.path().to_string()));
+            }
+        }
+        Ok(Outcome::Proven(result?))
+    }
+}
+
+/// Picks the prover from `--build-only <file>`, `--mock-proofs`,
+/// `--proof-server`, `--proof-timeout <secs>` and `--proof-retries <n>`,
+/// falling back to in-process proving.
+pub async fn from_args(
+    args: &cli::Args,
+    network_id: &str,
+) -> Result<Prover, Box<dyn std::error::Error + Send + Sync>> {
+    if let Some(path) = args.value("build-only") {
+        println!("✓ Building only; the unproven transaction goes to {path}");
+        let export = Arc::new(txfile::ExportingProver::new(path, network_id));
+        return Ok(Prover {
+            provider: export.clone(),
+            export: Some(export),
+        });
+    }
+
+    if args.flag("mock-proofs") {
+        println!(
+            "⚠️ Using mock proofs; only a node accepting mock proofs will take this transaction"
+        );
+        return Ok(Prover::new(Arc::new(MockProofServer)));
+    }
+
+    let Some(endpoint) = args.value("proof-server") else {
+        return Ok(Prover::new(Arc::new(LocalProofServer::new())));
+    };
+
+    let mut config = RemoteProofServerConfig::new(endpoint);
+    if let Some(secs) = args.value("proof-timeout") {
+        config.timeout = Duration::from_secs(secs.parse()?);
+    }
+    if let Some(retries) = args.value("proof-retries") {
+        config.retries = retries.parse()?;
+    }
+
+    let server = RemoteProofServer::new(config)?;
+    server
+        .check_health()
+        .await
+        .map_err(|e| format!("Proof server {endpoint} is not reachable: {e}"))?;
+    println!("✓ Using proof server {endpoint}");
+    Ok(Prover::new(Arc::new(server)))
+}
@@ -0,0 +1,92 @@
+//! # Block rewards
+//!
+//! Rewards accrue on-chain per unshielded address until claimed. A claim is a
+//! `ClaimRewards` transaction signed by the address's key; it needs no proof.
+//! When it is applied the Midnight pallet emits `ClaimRewards` with the
+//! claimed value and `PayoutMinted` for the UTXO paid to the receiver.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::wallet::UnshieldedWallet;
+use midnight_node_ledger_helpers::*;
+
+use rand::Rng;
+use subxt::blocks::ExtrinsicEvents;
+
+use crate::chain;
+use crate::midnight;
+use crate::midnight::api::midnight::events::{ClaimRewards, PayoutMinted};
+
+/// Rewards not yet claimed by `address`.
+pub async fn fetch_unclaimed(
+    api: &chain::Api,
+    address: &UserAddress,
+) -> Result<u128, Box<dyn std::error::Error + Send + Sync>> {
+    let amount = api
+        .runtime_api()
+        .at_latest()
+        .await?
+        .call(
+            midnight::api::apis()
+                .midnight_runtime_api()
+                .get_unclaimed_amount(address.0 .0.to_vec()),
+        )
+        .await?
+        .map_err(|e| format!("get_unclaimed_amount failed: {e:?}"))?;
+    Ok(amount)
+}
+
+/// The unshielded address rewards for `seed` are paid to.
+pub fn reward_address(seed: WalletSeed) -> UserAddress {
+    let wallet = UnshieldedWallet::default(seed);
+    UserAddress::from(wallet.signing_key().verifying_key())
+}
+
+/// Builds and signs a transaction claiming `value` of `seed`'s rewards.
+pub fn build_claim(
+    network_id: &str,
+    seed: WalletSeed,
+    value: u128,
+    rng: &mut StdRng,
+) -> FinalizedTransaction<DefaultDB> {
+    let wallet = UnshieldedWallet::default(seed);
+    let signing_key = wallet.signing_key();
+
+    let unsigned = ClaimRewardsTransaction {
+        network_id: network_id.to_string(),
+        value,
+        owner: signing_key.verifying_key(),
+        nonce: Nonce(HashOutput(rng.gen())),
+        signature: (),
+        kind: ClaimKind::Reward,
+    };
+    let signature = signing_key.sign(rng, &unsigned.data_to_sign());
+
+    Transaction::ClaimRewards(ClaimRewardsTransaction {
+        network_id: unsigned.network_id,
+        value: unsigned.value,
+        owner: unsigned.owner,
+        nonce: unsigned.nonce,
+        signature,
+        kind: unsigned.kind,
+    })
+}
+
+/// Prints the `ClaimRewards` and `PayoutMinted` events of a submitted claim
+/// and returns the total claimed.
+pub fn print_reward_events(events: &ExtrinsicEvents<subxt::PolkadotConfig>) -> u128 {
+    let mut claimed = 0;
+    for ev in events.find::<ClaimRewards>().filter_map(Result::ok) {
+        claimed += ev.0.value;
+        println!("  ClaimRewards:");
+        println!("    Tx hash: 0x{}", hex::encode(ev.0.tx_hash));
+        println!("    Value:   {}", ev.0.value);
+    }
+    for ev in events.find::<PayoutMinted>().filter_map(Result::ok) {
+        println!("  PayoutMinted:");
+        println!("    Receiver: 0x{}", hex::encode(&ev.0.receiver));
+        println!("    Amount:   {}", ev.0.amount);
+    }
+    claimed
+}
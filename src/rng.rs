@@ -0,0 +1,91 @@
+//! # Deterministic randomness
+//!
+//! Transaction building draws on an RNG for coin nonces, commitments and
+//! signatures, and witnesses may draw on one too (a first BBoard post's
+//! secret key). `--rng-seed` fixes both, so the same inputs and seed build a
+//! byte-identical unproven transaction, for snapshot tests and reproducing
+//! bug reports. Without it every run uses fresh entropy.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use sha2::{Digest, Sha256};
+
+use crate::cli;
+
+pub type RngSeed = [u8; 32];
+
+/// Parses a seed: 64 hex characters are taken as the seed itself, anything
+/// else (e.g. `42`) is hashed into one.
+pub fn parse_seed(value: &str) -> RngSeed {
+    match hex::decode(value) {
+        Ok(bytes) if bytes.len() == 32 => bytes.try_into().unwrap(),
+        _ => Sha256::digest(value.as_bytes()).into(),
+    }
+}
+
+/// The seed given with `--rng-seed`, if any.
+pub fn seed_from_args(args: &cli::Args) -> Option<RngSeed> {
+    let seed = parse_seed(args.value("rng-seed")?);
+    println!("⚠️ Deterministic RNG seed {}", hex::encode(seed));
+    Some(seed)
+}
+
+/// A seed for one independent use of `seed`, so two consumers seeded from
+/// the same `--rng-seed` never share a stream.
+pub fn derive(seed: &RngSeed, label: &str) -> RngSeed {
+    let mut hasher = Sha256::new();
+    hasher.update(label.as_bytes());
+    hasher.update(seed);
+    hasher.finalize().into()
+}
+
+/// `StdRng` seeded from `seed`, or from entropy without one.
+pub fn rng(seed: Option<RngSeed>, label: &str) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::from_seed(derive(&seed, label)),
+        None => StdRng::from_entropy(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngCore;
+
+    #[test]
+    fn hex_seeds_are_taken_as_is() {
+        let hex_seed = "ab".repeat(32);
+        assert_eq!(parse_seed(&hex_seed), [0xab; 32]);
+        assert_eq!(parse_seed(&hex_seed.to_uppercase()), [0xab; 32]);
+    }
+
+    #[test]
+    fn other_seeds_are_hashed() {
+        let expected: RngSeed = Sha256::digest(b"42").into();
+        assert_eq!(parse_seed("42"), expected);
+        // Too short to be a seed itself
+        assert_eq!(parse_seed("abab"), <RngSeed>::from(Sha256::digest(b"abab")));
+        assert_ne!(parse_seed("42"), parse_seed("43"));
+    }
+
+    #[test]
+    fn derived_seeds_differ_per_label() {
+        let seed = parse_seed("42");
+        assert_eq!(derive(&seed, "tx"), derive(&seed, "tx"));
+        assert_ne!(derive(&seed, "tx"), derive(&seed, "witnesses"));
+        assert_ne!(derive(&seed, "tx"), seed);
+    }
+
+    #[test]
+    fn seeded_rngs_repeat() {
+        let seed = Some(parse_seed("42"));
+        assert_eq!(rng(seed, "tx").next_u64(), rng(seed, "tx").next_u64());
+        assert_ne!(
+            rng(seed, "tx").next_u64(),
+            rng(seed, "witnesses").next_u64()
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! # Signed extrinsics
+//!
+//! Midnight transactions go in unsigned (`chain::submit_transaction`), but
+//! governance, session keys, `TxPause` and `SystemParameters` calls need a
+//! signed origin. An [`Account`] is an sr25519 or ed25519 Substrate key from
+//! one of:
+//!
+//! - a dev account name (`alice`, `bob`, ...), i.e. `//Alice`
+//! - a secret URI: a mnemonic or `0x` seed with `//hard`, `/soft` and
+//!   `///password` parts
+//! - a keystore directory in Substrate's layout: one file per key, named
+//!   `hex(key type) ++ hex(public key)` and holding the secret URI as a JSON
+//!   string
+//!
+//! A [`Sender`] signs any `midnight::api::tx()` payload with an account and
+//! [`TxParams`]: the nonce (from `AccountNonceApi` unless given), mortality
+//! and tip.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use subxt::config::DefaultExtrinsicParamsBuilder;
+use subxt::utils::{AccountId32, MultiSignature};
+use subxt_signer::{ed25519, sr25519, SecretUri};
+
+use crate::chain::{self, Api, Submitted};
+use crate::cli;
+use crate::midnight;
+
+pub const DEV_ACCOUNTS: &[&str] = &["alice", "bob", "charlie", "dave", "eve", "ferdie"];
+
+/// Key type of account keys in a keystore (`sp_core`'s `ACCOUNT`).
+pub const ACCOUNT_KEY_TYPE: [u8; 4] = *b"acco";
+
+// ─── Accounts ────────────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    Sr25519,
+    Ed25519,
+}
+
+impl FromStr for Scheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sr25519" => Ok(Scheme::Sr25519),
+            "ed25519" => Ok(Scheme::Ed25519),
+            _ => Err(format!("Unknown scheme {s}; expected sr25519 or ed25519")),
+        }
+    }
+}
+
+enum Keypair {
+    Sr25519(sr25519::Keypair),
+    Ed25519(ed25519::Keypair),
+}
+
+pub struct Account {
+    keypair: Keypair,
+}
+
+impl Account {
+    /// The account for a dev account name or a secret URI.
+    pub fn parse(
+        scheme: Scheme,
+        suri: &str,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let suri = match DEV_ACCOUNTS
+            .iter()
+            .find(|name| name.eq_ignore_ascii_case(suri))
+        {
+            Some(name) => format!("//{}{}", name[..1].to_uppercase(), &name[1..]),
+            None => suri.to_string(),
+        };
+        let uri = SecretUri::from_str(&suri)?;
+        let keypair = match scheme {
+            Scheme::Sr25519 => Keypair::Sr25519(sr25519::Keypair::from_uri(&uri)?),
+            Scheme::Ed25519 => Keypair::Ed25519(ed25519::Keypair::from_uri(&uri)?),
+        };
+        Ok(Self { keypair })
+    }
+
+    /// The account from `--suri` (default `alice`) or, with `--keystore <dir>`,
+    /// the key `--key <public key>` in that keystore. `--scheme` picks
+    /// sr25519 (default) or ed25519.
+    pub fn from_args(args: &cli::Args) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let scheme = args.value_or("scheme", "sr25519").parse()?;
+        match args.value("keystore") {
+            Some(dir) => Keystore::new(dir).load(scheme, &parse_public(args.require("key")?)?),
+            None => Self::parse(scheme, args.value_or("suri", "alice")),
+        }
+    }
+
+    pub fn scheme(&self) -> Scheme {
+        match self.keypair {
+            Keypair::Sr25519(_) => Scheme::Sr25519,
+            Keypair::Ed25519(_) => Scheme::Ed25519,
+        }
+    }
+
+    pub fn public_key(&self) -> [u8; 32] {
+        match &self.keypair {
+            Keypair::Sr25519(pair) => pair.public_key().0,
+            Keypair::Ed25519(pair) => pair.public_key().0,
+        }
+    }
+
+    pub fn account_id(&self) -> AccountId32 {
+        AccountId32(self.public_key())
+    }
+
+    /// The next nonce according to the runtime's `AccountNonceApi`.
+    pub async fn nonce(&self, api: &Api) -> Result<u64, Box<dyn std::error::Error + Send + Sync>> {
+        let nonce = api
+            .runtime_api()
+            .at_latest()
+            .await?
+            .call(
+                midnight::api::apis()
+                    .account_nonce_api()
+                    .account_nonce(self.account_id()),
+            )
+            .await?;
+        Ok(nonce as u64)
+    }
+}
+
+impl subxt::tx::Signer<subxt::PolkadotConfig> for Account {
+    fn account_id(&self) -> AccountId32 {
+        Account::account_id(self)
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+        match &self.keypair {
+            Keypair::Sr25519(pair) => MultiSignature::Sr25519(pair.sign(signer_payload).0),
+            Keypair::Ed25519(pair) => MultiSignature::Ed25519(pair.sign(signer_payload).0),
+        }
+    }
+}
+
+fn parse_public(hex_str: &str) -> Result<[u8; 32], Box<dyn std::error::Error + Send + Sync>> {
+    hex::decode(hex_str.trim_start_matches("0x"))?
+        .try_into()
+        .map_err(|_| format!("Public keys are 32 bytes: {hex_str}").into())
+}
+
+// ─── Keystore ────────────────────────────────────────────────────────────────
+
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn key_path(&self, public: &[u8; 32]) -> PathBuf {
+        self.dir.join(format!(
+            "{}{}",
+            hex::encode(ACCOUNT_KEY_TYPE),
+            hex::encode(public)
+        ))
+    }
+
+    /// Public keys of the account keys in the keystore.
+    pub fn list(&self) -> Result<Vec<[u8; 32]>, Box<dyn std::error::Error + Send + Sync>> {
+        let prefix = hex::encode(ACCOUNT_KEY_TYPE);
+        let mut keys = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let name = entry?.file_name().to_string_lossy().into_owned();
+            if let Some(public) = name.strip_prefix(&prefix) {
+                if let Ok(public) = parse_public(public) {
+                    keys.push(public);
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    /// Stores `suri` and returns its account.
+    pub fn insert(
+        &self,
+        scheme: Scheme,
+        suri: &str,
+    ) -> Result<Account, Box<dyn std::error::Error + Send + Sync>> {
+        let account = Account::parse(scheme, suri)?;
+        std::fs::create_dir_all(&self.dir)?;
+        std::fs::write(
+            self.key_path(&account.public_key()),
+            serde_json::to_string(suri)?,
+        )?;
+        Ok(account)
+    }
+
+    /// The account whose public key is `public`. The keystore doesn't record
+    /// the scheme, so a key read with the wrong one fails the public key check.
+    pub fn load(
+        &self,
+        scheme: Scheme,
+        public: &[u8; 32],
+    ) -> Result<Account, Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.key_path(public);
+        let contents = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Cannot read key {}: {e}", path.display()))?;
+        let suri: String = serde_json::from_str(&contents)?;
+        let account = Account::parse(scheme, &suri)?;
+        if account.public_key() != *public {
+            return Err(format!(
+                "Key {} is not a {scheme:?} key for 0x{}",
+                path.display(),
+                hex::encode(public)
+            )
+            .into());
+        }
+        Ok(account)
+    }
+}
+
+// ─── Submitting ──────────────────────────────────────────────────────────────
+
+/// Extrinsic parameters beyond the signature.
+#[derive(Debug, Clone, Default)]
+pub struct TxParams {
+    /// Looked up through `AccountNonceApi` when `None`.
+    pub nonce: Option<u64>,
+    /// Blocks the extrinsic stays valid for; immortal when `None`.
+    pub mortality: Option<u64>,
+    pub tip: u128,
+}
+
+impl TxParams {
+    /// From `--nonce <n>`, `--mortal <blocks>` and `--tip <amount>`.
+    pub fn from_args(args: &cli::Args) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            nonce: args.value("nonce").map(str::parse).transpose()?,
+            mortality: args.value("mortal").map(str::parse).transpose()?,
+            tip: args.value_or("tip", "0").parse()?,
+        })
+    }
+}
+
+/// An account and the parameters it signs extrinsics with.
+pub struct Sender {
+    pub account: Account,
+    pub params: TxParams,
+}
+
+impl Sender {
+    /// See [`Account::from_args`] and [`TxParams::from_args`].
+    pub fn from_args(args: &cli::Args) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            account: Account::from_args(args)?,
+            params: TxParams::from_args(args)?,
+        })
+    }
+
+    /// Signs `payload`, submits it and waits for finalization, like
+    /// `chain::submit_transaction`.
+    pub async fn submit<P: subxt::tx::Payload>(
+        &self,
+        api: &Api,
+        payload: &P,
+    ) -> Result<Option<Submitted>, Box<dyn std::error::Error + Send + Sync>> {
+        println!("\n=== Submitting Signed Extrinsic ===\n");
+
+        let nonce = match self.params.nonce {
+            Some(nonce) => nonce,
+            None => self.account.nonce(api).await?,
+        };
+        println!(
+            "  Signer: {} ({:?})",
+            self.account.account_id(),
+            self.account.scheme()
+        );
+        println!("  Nonce:  {nonce}");
+
+        let mut params = DefaultExtrinsicParamsBuilder::<subxt::PolkadotConfig>::new()
+            .nonce(nonce)
+            .tip(self.params.tip);
+        if let Some(blocks) = self.params.mortality {
+            params = params.mortal(blocks);
+        }
+
+        let progress = api
+            .tx()
+            .sign_and_submit_then_watch(payload, &self.account, params.build())
+            .await?;
+        chain::watch_submission(progress).await
+    }
+}
@@ -0,0 +1,178 @@
+//! # System transaction classification
+//!
+//! System transactions change ledger state without a user's signature or
+//! proof, so auditing a chain means knowing what each one was and who caused
+//! it. [`classify`] names the transaction, lists its effects and tags its
+//! origin from the extrinsic that carried or triggered it.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+use std::fmt;
+
+use crate::chain::BlockTransaction;
+
+/// Where a system transaction came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    /// A `send_mn_system_transaction` extrinsic in block 0.
+    GenesisExtrinsic,
+    /// `MidnightSystem.send_mn_system_transaction` called directly.
+    DirectCall,
+    /// Dispatched by a governance body closing or executing a motion; holds
+    /// the `Pallet.call` that did it.
+    Governance(String),
+    /// Applied while processing observed cNight (Cardano) activity.
+    CNightObservation,
+    /// Applied while processing observed federated authority (Cardano
+    /// governance) membership.
+    FederatedAuthorityObservation,
+    /// Any other extrinsic.
+    Other(String),
+}
+
+impl Origin {
+    /// Pallet names are those of the runtime (`midnight::PALLETS`); it has
+    /// no `Sudo`, so root calls only come from governance.
+    pub fn of(block_number: u64, tx: &BlockTransaction) -> Self {
+        let pallet = tx.call.split('.').next().unwrap_or_default();
+        match pallet {
+            _ if block_number == 0 => Origin::GenesisExtrinsic,
+            "MidnightSystem" => Origin::DirectCall,
+            "Council" | "TechnicalCommittee" | "FederatedAuthority" | "Scheduler"
+            | "SystemParameters" => Origin::Governance(tx.call.clone()),
+            "CNightObservation" => Origin::CNightObservation,
+            "FederatedAuthorityObservation" => Origin::FederatedAuthorityObservation,
+            _ => Origin::Other(tx.call.clone()),
+        }
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Origin::GenesisExtrinsic => write!(f, "genesis extrinsic"),
+            Origin::DirectCall => write!(f, "direct call"),
+            Origin::Governance(call) => write!(f, "governance ({call})"),
+            Origin::CNightObservation => write!(f, "cNight observation"),
+            Origin::FederatedAuthorityObservation => {
+                write!(f, "federated authority observation")
+            }
+            Origin::Other(call) => write!(f, "other ({call})"),
+        }
+    }
+}
+
+pub struct Classified {
+    /// e.g. `cNight distribution`, `parameter update`.
+    pub kind: &'static str,
+    /// Unknown for a transaction read outside its block.
+    pub origin: Option<Origin>,
+    /// One line per effect on the ledger.
+    pub effects: Vec<String>,
+}
+
+/// Names `tx`, describes its effects and tags its origin.
+pub fn classify(tx: &SystemTransaction, origin: Option<Origin>) -> Classified {
+    let (kind, effects) = match tx {
+        SystemTransaction::OverwriteParameters(params) => (
+            "parameter update",
+            vec![
+                format!("fee prices ← {:?}", params.fee_prices),
+                format!("limits ← {:?}", params.limits),
+                format!("DUST ← {:?}", params.dust),
+            ],
+        ),
+        SystemTransaction::DistributeNight(claim_kind, outputs) => {
+            let kind = match (claim_kind, &origin) {
+                (_, Some(Origin::GenesisExtrinsic)) => "genesis allocation",
+                (ClaimKind::Reward, _) => "reward payout",
+                _ => "cNight distribution",
+            };
+            let effects = outputs
+                .iter()
+                .map(|out| {
+                    format!(
+                        "{} NIGHT claimable by {}",
+                        out.amount,
+                        hex::encode(out.target_address.0 .0)
+                    )
+                })
+                .collect();
+            (kind, effects)
+        }
+        SystemTransaction::PayBlockRewardsToTreasury { amount } => (
+            "block rewards to treasury",
+            vec![format!("{amount} NIGHT into the treasury")],
+        ),
+        SystemTransaction::PayFromTreasuryShielded {
+            outputs,
+            token_type,
+            ..
+        } => (
+            "treasury payout (shielded)",
+            outputs
+                .iter()
+                .map(|out| {
+                    format!(
+                        "{} of {} to {}",
+                        out.amount,
+                        hex::encode(token_type.0 .0),
+                        hex::encode(out.target_key.0 .0)
+                    )
+                })
+                .collect(),
+        ),
+        SystemTransaction::PayFromTreasuryUnshielded {
+            outputs,
+            token_type,
+        } => (
+            "treasury payout (unshielded)",
+            outputs
+                .iter()
+                .map(|out| {
+                    format!(
+                        "{} of {} to {}",
+                        out.amount,
+                        hex::encode(token_type.0 .0),
+                        hex::encode(out.target_address.0 .0)
+                    )
+                })
+                .collect(),
+        ),
+        SystemTransaction::DistributeReserve(amount) => (
+            "reserve distribution",
+            vec![format!("{amount} NIGHT from the reserve")],
+        ),
+        SystemTransaction::CNightGeneratesDustUpdate { events } => (
+            "cNight DUST generation update",
+            events
+                .iter()
+                .map(|event| {
+                    format!(
+                        "{:?} {} cNight generating DUST for {:?}",
+                        event.action, event.value, event.owner
+                    )
+                })
+                .collect(),
+        ),
+        #[allow(unreachable_patterns)]
+        other => ("unknown", vec![format!("{other:?}")]),
+    };
+    Classified {
+        kind,
+        origin,
+        effects,
+    }
+}
+
+pub fn print(classified: &Classified) {
+    println!("  Kind:   {}", classified.kind);
+    if let Some(origin) = &classified.origin {
+        println!("  Origin: {origin}");
+    }
+    for effect in &classified.effects {
+        println!("    {effect}");
+    }
+}
@@ -0,0 +1,277 @@
+//! # Contract-held tokens
+//!
+//! Contracts hold balances per token type in `ContractState.balance`, read
+//! here from the node's `get_contract_state`.
+//!
+//! A call that moves tokens in or out of a contract only balances when the
+//! caller's side of the transaction mirrors the contract's effects:
+//!
+//! - shielded deposit:    caller coin in, coin out to the contract, change back
+//! - shielded withdraw:   coin out to the caller (the circuit spends its own)
+//! - unshielded deposit:  caller UTXO spent, change back, the rest claimed by
+//!   the contract's `unshielded_inputs` effect
+//! - unshielded withdraw: UTXO out to the caller, matching the contract's
+//!   `unshielded_outputs` effect
+//!
+//! A contract says which tokens its circuits move through [`TokenEffects`];
+//! [`fund_call`] builds the caller's side of those movements. The contract's
+//! own `Contract` implementation still produces the transcript with the
+//! matching effects.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use std::any::Any;
+
+use crate::chain;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Tokens move from the caller's wallet into the contract.
+    Deposit,
+    /// Tokens move from the contract to the caller's wallet.
+    Withdraw,
+}
+
+#[derive(Clone, Debug)]
+pub struct TokenMovement {
+    pub token_type: TokenType,
+    pub amount: u128,
+    pub direction: Direction,
+}
+
+/// The caller's side of a token-moving contract call.
+pub struct CallFunding {
+    pub guaranteed_offer: OfferInfo<DefaultDB>,
+    pub unshielded_offer: Option<UnshieldedOfferInfo<DefaultDB>>,
+}
+
+/// The tokens a contract's circuits claim or emit.
+///
+/// Implemented next to each contract's `Contract` implementation; the default
+/// is a contract without token effects.
+pub trait TokenEffects {
+    /// The movements a call to circuit `key` with `input` makes.
+    fn token_movements(&self, _key: &str, _input: &(dyn Any + Send + Sync)) -> Vec<TokenMovement> {
+        Vec::new()
+    }
+}
+
+// ─── Balance queries ─────────────────────────────────────────────────────────
+
+/// Every non-zero balance held by the contract.
+pub fn contract_balances(state: &ContractState<DefaultDB>) -> Vec<(TokenType, u128)> {
+    state
+        .balance
+        .iter()
+        .map(|(token_type, amount)| (*token_type, *amount))
+        .filter(|(_, amount)| *amount > 0)
+        .collect()
+}
+
+pub fn contract_balance(state: &ContractState<DefaultDB>, token_type: TokenType) -> u128 {
+    state
+        .balance
+        .get(&token_type)
+        .map(|amount| *amount)
+        .unwrap_or(0)
+}
+
+/// Fetches the contract's current balance of `token_type` from the node.
+pub async fn fetch_contract_balance(
+    api: &chain::Api,
+    address: &ContractAddress,
+    token_type: TokenType,
+) -> Result<u128, Box<dyn std::error::Error + Send + Sync>> {
+    let state = chain::fetch_contract_state(api, address).await?;
+    Ok(contract_balance(&state, token_type))
+}
+
+/// Parses a token type as [`token_type_hex`] prints it; bare hex is a
+/// shielded token type.
+pub fn parse_token_type(
+    value: &str,
+) -> Result<TokenType, Box<dyn std::error::Error + Send + Sync>> {
+    if value == "dust" {
+        return Ok(TokenType::Dust);
+    }
+    let (kind, hex_str) = value.split_once(':').unwrap_or(("shielded", value));
+    let bytes: [u8; 32] = hex::decode(hex_str.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("Token types are 32 bytes of hex: {value}"))?;
+    match kind {
+        "shielded" => Ok(TokenType::Shielded(ShieldedTokenType(HashOutput(bytes)))),
+        "unshielded" => Ok(TokenType::Unshielded(UnshieldedTokenType(HashOutput(
+            bytes,
+        )))),
+        _ => {
+            Err(format!("Unknown token kind {kind}; expected shielded, unshielded or dust").into())
+        }
+    }
+}
+
+pub fn token_type_hex(token_type: &TokenType) -> String {
+    match token_type {
+        TokenType::Shielded(t) => format!("shielded:{}", hex::encode(t.0 .0)),
+        TokenType::Unshielded(t) => format!("unshielded:{}", hex::encode(t.0 .0)),
+        TokenType::Dust => "dust".to_string(),
+    }
+}
+
+// ─── Call funding ────────────────────────────────────────────────────────────
+
+/// Builds the caller's offers for `movements` into or out of `address`.
+///
+/// Withdrawals are checked against the contract's current balance so an
+/// overdrawn call fails here rather than at proving time.
+pub fn fund_call(
+    movements: &[TokenMovement],
+    caller: WalletSeed,
+    address: ContractAddress,
+    context: &LedgerContext<DefaultDB>,
+    state: &ContractState<DefaultDB>,
+) -> Result<CallFunding, Box<dyn std::error::Error + Send + Sync>> {
+    let wallet = context.wallet_from_seed(caller);
+
+    let mut inputs: Vec<Box<dyn BuildInput<DefaultDB>>> = Vec::new();
+    let mut outputs: Vec<Box<dyn BuildOutput<DefaultDB>>> = Vec::new();
+    let mut utxo_spends: Vec<Box<dyn BuildUtxoSpend<DefaultDB>>> = Vec::new();
+    let mut utxo_outputs: Vec<Box<dyn BuildUtxoOutput<DefaultDB>>> = Vec::new();
+
+    for movement in movements {
+        let token = token_type_hex(&movement.token_type);
+        if movement.direction == Direction::Withdraw {
+            let held = contract_balance(state, movement.token_type);
+            if held < movement.amount {
+                return Err(format!(
+                    "Contract holds {held} of {token}, cannot withdraw {}",
+                    movement.amount
+                )
+                .into());
+            }
+        }
+        let short = |available: u128| {
+            format!(
+                "No single {token} coin covers {}; the largest match holds {available}",
+                movement.amount
+            )
+        };
+
+        match (movement.token_type, movement.direction) {
+            (TokenType::Shielded(token_type), Direction::Deposit) => {
+                let input = InputInfo {
+                    origin: caller,
+                    token_type,
+                    value: movement.amount,
+                };
+                // Same coin selection as the toolkit: smallest coin >= amount
+                let available = input.min_match_coin(&wallet.shielded.state).value;
+                let change = available
+                    .checked_sub(movement.amount)
+                    .ok_or_else(|| short(available))?;
+                inputs.push(Box::new(input));
+                outputs.push(Box::new(OutputInfo {
+                    destination: address,
+                    token_type,
+                    value: movement.amount,
+                }));
+                if change > 0 {
+                    outputs.push(Box::new(OutputInfo {
+                        destination: caller,
+                        token_type,
+                        value: change,
+                    }));
+                }
+            }
+            (TokenType::Shielded(token_type), Direction::Withdraw) => {
+                outputs.push(Box::new(OutputInfo {
+                    destination: caller,
+                    token_type,
+                    value: movement.amount,
+                }));
+            }
+            (TokenType::Unshielded(token_type), Direction::Deposit) => {
+                let spend = UtxoSpendInfo {
+                    value: movement.amount,
+                    owner: caller,
+                    token_type,
+                    intent_hash: None,
+                    output_number: None,
+                };
+                let available = spend.min_match_utxo(context)?.value;
+                let change = available
+                    .checked_sub(movement.amount)
+                    .ok_or_else(|| short(available))?;
+                utxo_spends.push(Box::new(spend));
+                if change > 0 {
+                    utxo_outputs.push(Box::new(UtxoOutputInfo {
+                        value: change,
+                        owner: caller,
+                        token_type,
+                    }));
+                }
+            }
+            (TokenType::Unshielded(token_type), Direction::Withdraw) => {
+                utxo_outputs.push(Box::new(UtxoOutputInfo {
+                    value: movement.amount,
+                    owner: caller,
+                    token_type,
+                }));
+            }
+            (TokenType::Dust, _) => {
+                return Err("DUST cannot be held by contracts".into());
+            }
+        }
+    }
+
+    let unshielded_offer =
+        (!utxo_spends.is_empty() || !utxo_outputs.is_empty()).then(|| UnshieldedOfferInfo {
+            inputs: utxo_spends,
+            outputs: utxo_outputs,
+        });
+
+    Ok(CallFunding {
+        guaranteed_offer: OfferInfo {
+            inputs,
+            outputs,
+            transients: vec![],
+        },
+        unshielded_offer,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEX: &str = "0000000000000000000000000000000000000000000000000000000000000002";
+
+    #[test]
+    fn token_types_round_trip() {
+        for value in [
+            format!("shielded:{HEX}"),
+            format!("unshielded:{HEX}"),
+            "dust".into(),
+        ] {
+            let token_type = parse_token_type(&value).unwrap();
+            assert_eq!(token_type_hex(&token_type), value);
+        }
+    }
+
+    #[test]
+    fn bare_hex_is_shielded() {
+        let token_type = parse_token_type(HEX).unwrap();
+        assert_eq!(token_type_hex(&token_type), format!("shielded:{HEX}"));
+    }
+
+    #[test]
+    fn bad_token_types_are_errors() {
+        let minted = format!("minted:{HEX}");
+        for value in ["", "zz", "shielded:00", &minted, &HEX[2..]] {
+            assert!(parse_token_type(value).is_err(), "{value}");
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! # Offline transaction files
+//!
+//! Building, proving and submitting can run as separate stages on separate
+//! machines, handing a JSON file from one to the next:
+//!
+//! 1. `--build-only <file>` on a building binary writes the unproven
+//!    transaction and the cost model it was built against
+//! 2. `offline_tx prove <file> --out <file>` proves it, with no node needed
+//! 3. `offline_tx submit <file>` sends the proven bytes through
+//!    `send_mn_transaction`
+//!
+//! Every file carries its stage, a format version and the network id it was
+//! built for; readers refuse a file of the wrong stage, version or network.
+//!
+//! The ledger context the transaction was built from is not exported. It
+//! only matters while building (coin selection, contract transcripts); the
+//! unproven transaction's proof preimages already hold every circuit input,
+//! so `offline_tx prove` needs just the transaction, its cost model and the
+//! circuit keys. Whether the transaction still applies is for the node to
+//! check at `submit`.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use serde::{Deserialize, Serialize};
+
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Bumped whenever the file layout or the transaction encoding changes.
+pub const TX_FILE_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Stage {
+    Unproven,
+    Proven,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TxFile {
+    pub stage: Stage,
+    pub version: u32,
+    pub network_id: String,
+    /// Tagged-serialized transaction, hex-encoded.
+    pub tx: String,
+    /// Tagged-serialized `CostModel`, hex-encoded; unproven files only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_model: Option<String>,
+}
+
+impl TxFile {
+    pub fn unproven(
+        network_id: &str,
+        tx: &Transaction<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB>,
+        cost_model: &CostModel,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            stage: Stage::Unproven,
+            version: TX_FILE_VERSION,
+            network_id: network_id.to_string(),
+            tx: hex::encode(serialize(tx)?),
+            cost_model: Some(hex::encode(serialize(cost_model)?)),
+        })
+    }
+
+    pub fn proven(
+        network_id: &str,
+        tx: &Transaction<Signature, ProofMarker, PedersenRandomness, DefaultDB>,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(Self {
+            stage: Stage::Proven,
+            version: TX_FILE_VERSION,
+            network_id: network_id.to_string(),
+            tx: hex::encode(serialize(tx)?),
+            cost_model: None,
+        })
+    }
+
+    /// Reads a file, checking it is at `stage` and in the current version.
+    pub fn read(
+        path: &str,
+        stage: Stage,
+    ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let contents = std::fs::read_to_string(path)?;
+        let file: Self = serde_json::from_str(&contents)
+            .map_err(|e| format!("{path} is not a transaction file: {e}"))?;
+        if file.version != TX_FILE_VERSION {
+            return Err(format!(
+                "{path} has format version {}, expected {TX_FILE_VERSION}",
+                file.version
+            )
+            .into());
+        }
+        if file.stage != stage {
+            return Err(format!(
+                "{path} holds a {:?} transaction, expected {stage:?}",
+                file.stage
+            )
+            .into());
+        }
+        Ok(file)
+    }
+
+    pub fn write(&self, path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn check_network(
+        &self,
+        network_id: &str,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.network_id != network_id {
+            return Err(format!(
+                "Transaction was built for network {} but the target is {network_id}",
+                self.network_id
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    pub fn tx_bytes(&self) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(hex::decode(&self.tx)?)
+    }
+
+    pub fn unproven_tx(
+        &self,
+    ) -> Result<
+        Transaction<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB>,
+        Box<dyn std::error::Error + Send + Sync>,
+    > {
+        Ok(deserialize(&mut self.tx_bytes()?.as_slice())?)
+    }
+
+    pub fn cost_model(&self) -> Result<CostModel, Box<dyn std::error::Error + Send + Sync>> {
+        let encoded = self
+            .cost_model
+            .as_deref()
+            .ok_or("Transaction file has no cost model")?;
+        Ok(deserialize(&mut hex::decode(encoded)?.as_slice())?)
+    }
+}
+
+// ─── Export ──────────────────────────────────────────────────────────────────
+
+/// A `ProofProvider` that writes the transaction it is handed to an unproven
+/// file instead of proving it, then fails so that building stops there:
+/// nothing is proven, mock or otherwise. `prover::Prover::prove` turns that
+/// failure into `Outcome::Exported` (see [`ExportingProver::take_written`]).
+pub struct ExportingProver {
+    path: String,
+    network_id: String,
+    /// Set by `prove` once the file is written, cleared by `take_written`.
+    written: AtomicBool,
+}
+
+impl ExportingProver {
+    pub fn new(path: &str, network_id: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            network_id: network_id.to_string(),
+            written: AtomicBool::new(false),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Whether a file was written since the last call, i.e. whether the
+    /// builder's failure is this prover stopping it.
+    pub fn take_written(&self) -> bool {
+        self.written.swap(false, Ordering::SeqCst)
+    }
+}
+
+#[async_trait]
+impl ProofProvider<DefaultDB> for ExportingProver {
+    async fn prove(
+        &self,
+        tx: Transaction<Signature, ProofPreimageMarker, PedersenRandomness, DefaultDB>,
+        _rng: StdRng,
+        _resolver: &Resolver,
+        cost_model: &CostModel,
+    ) -> Result<Transaction<Signature, ProofMarker, PedersenRandomness, DefaultDB>, anyhow::Error>
+    {
+        TxFile::unproven(&self.network_id, &tx, cost_model)
+            .and_then(|file| file.write(&self.path))
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {e}", self.path))?;
+        println!("✓ Unproven transaction written to {}", self.path);
+        self.written.store(true, Ordering::SeqCst);
+
+        Err(anyhow::anyhow!("Stopped before proving (--build-only)"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(name: &str, file: &TxFile) -> String {
+        let path = std::env::temp_dir()
+            .join(format!("txfile-test-{}-{name}.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        file.write(&path).unwrap();
+        path
+    }
+
+    fn file(stage: Stage, version: u32) -> TxFile {
+        TxFile {
+            stage,
+            version,
+            network_id: "undeployed".to_string(),
+            tx: "00".to_string(),
+            cost_model: None,
+        }
+    }
+
+    #[test]
+    fn files_of_the_expected_stage_are_read() {
+        let path = write("proven", &file(Stage::Proven, TX_FILE_VERSION));
+        let read = TxFile::read(&path, Stage::Proven).unwrap();
+        assert_eq!(read.network_id, "undeployed");
+        assert_eq!(read.tx_bytes().unwrap(), [0]);
+    }
+
+    #[test]
+    fn files_of_another_stage_are_refused() {
+        let path = write("stage", &file(Stage::Unproven, TX_FILE_VERSION));
+        let err = TxFile::read(&path, Stage::Proven).unwrap_err();
+        assert!(err.to_string().contains("expected Proven"), "{err}");
+    }
+
+    #[test]
+    fn files_of_another_version_are_refused() {
+        let path = write("version", &file(Stage::Proven, TX_FILE_VERSION + 1));
+        let err = TxFile::read(&path, Stage::Proven).unwrap_err();
+        assert!(err.to_string().contains("format version"), "{err}");
+    }
+
+    #[test]
+    fn other_json_is_refused() {
+        let path =
+            std::env::temp_dir().join(format!("txfile-test-{}-other.json", std::process::id()));
+        std::fs::write(&path, "{\"hello\": 1}").unwrap();
+        let err = TxFile::read(&path.to_string_lossy(), Stage::Proven).unwrap_err();
+        assert!(err.to_string().contains("not a transaction file"), "{err}");
+    }
+
+    #[test]
+    fn networks_must_match() {
+        let file = file(Stage::Proven, TX_FILE_VERSION);
+        assert!(file.check_network("undeployed").is_ok());
+        assert!(file.check_network("testnet").is_err());
+    }
+}
@@ -0,0 +1,209 @@
+//! # Witnesses and private state
+//!
+//! Compact circuits read private inputs through `witness` declarations (e.g.
+//! BBoard's `localSecretKey()`). A [`WitnessProvider`] answers those calls
+//! during transcript generation, reading and updating the caller's
+//! [`PrivateState`].
+//!
+//! Private state is persisted by [`PrivateStateStore`] per contract address
+//! and account, so a later run (or a later circuit) sees what an earlier one
+//! stored, e.g. the secret key that made a BBoard post. Witnesses only stage
+//! their changes; the caller commits them once the call is on chain, so a
+//! failed call leaves nothing behind.
+
+// Shared between binaries; not every binary uses every item.
+#![allow(dead_code)]
+
+use midnight_node_ledger_helpers::*;
+
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Default directory for private state, relative to the project root.
+pub const PRIVATE_STATE_DIR: &str = "private-state";
+
+/// A contract's private state for one account: named byte strings.
+pub type PrivateState = BTreeMap<String, Vec<u8>>;
+
+/// What a witness gets to see besides the private state.
+pub struct WitnessContext<'a> {
+    pub address: &'a ContractAddress,
+    /// The contract's public state at the time of the call.
+    pub ledger: &'a StateValue<DefaultDB>,
+}
+
+pub trait WitnessProvider: Send + Sync {
+    /// Evaluates witness `name`, possibly updating `private_state`. Any
+    /// randomness comes from `rng`, so `--rng-seed` covers witnesses too.
+    fn witness(
+        &self,
+        name: &str,
+        context: &WitnessContext,
+        private_state: &mut PrivateState,
+        rng: &mut StdRng,
+    ) -> Result<AlignedValue, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+/// Stable account identifier for a wallet seed, so stored state never
+/// contains the seed itself.
+pub fn account_id(seed: &WalletSeed) -> String {
+    let digest = Sha256::digest(seed.0);
+    hex::encode(&digest[..16])
+}
+
+// ─── Store ───────────────────────────────────────────────────────────────────
+
+/// JSON files under `<dir>/<contract address>/<account>.json`.
+pub struct PrivateStateStore {
+    dir: PathBuf,
+    /// States staged since the last `commit`, by file.
+    staged: Mutex<BTreeMap<PathBuf, PrivateState>>,
+}
+
+impl PrivateStateStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            staged: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn path(&self, address: &ContractAddress, account: &str) -> PathBuf {
+        self.dir
+            .join(hex::encode(address.0 .0))
+            .join(format!("{account}.json"))
+    }
+
+    /// Loads the private state, staged changes included; an account without
+    /// stored state starts empty.
+    pub fn load(
+        &self,
+        address: &ContractAddress,
+        account: &str,
+    ) -> Result<PrivateState, Box<dyn std::error::Error + Send + Sync>> {
+        let path = self.path(address, account);
+        let staged = self
+            .staged
+            .lock()
+            .map_err(|_| "Private state lock poisoned")?;
+        if let Some(state) = staged.get(&path) {
+            return Ok(state.clone());
+        }
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(PrivateState::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let encoded: BTreeMap<String, String> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Corrupt private state {}: {e}", path.display()))?;
+        encoded
+            .into_iter()
+            .map(|(key, value)| Ok((key, hex::decode(value)?)))
+            .collect()
+    }
+
+    /// Keeps `state` in memory until [`PrivateStateStore::commit`]; `load`
+    /// sees it meanwhile.
+    pub fn stage(
+        &self,
+        address: &ContractAddress,
+        account: &str,
+        state: PrivateState,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.staged
+            .lock()
+            .map_err(|_| "Private state lock poisoned")?
+            .insert(self.path(address, account), state);
+        Ok(())
+    }
+
+    /// Writes every staged state, e.g. once the call that changed it is on
+    /// chain.
+    pub fn commit(&self) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let staged = std::mem::take(
+            &mut *self
+                .staged
+                .lock()
+                .map_err(|_| "Private state lock poisoned")?,
+        );
+        for (path, state) in &staged {
+            Self::write(path, state)?;
+        }
+        Ok(())
+    }
+
+    pub fn save(
+        &self,
+        address: &ContractAddress,
+        account: &str,
+        state: &PrivateState,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        Self::write(&self.path(address, account), state)
+    }
+
+    fn write(
+        path: &Path,
+        state: &PrivateState,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let encoded: BTreeMap<&String, String> = state
+            .iter()
+            .map(|(key, value)| (key, hex::encode(value)))
+            .collect();
+        std::fs::write(path, serde_json::to_string_pretty(&encoded)?)?;
+        Ok(())
+    }
+}
+
+impl Default for PrivateStateStore {
+    fn default() -> Self {
+        Self::new(PRIVATE_STATE_DIR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(name: &str) -> PrivateStateStore {
+        PrivateStateStore::new(
+            std::env::temp_dir().join(format!("witness-test-{}-{name}", std::process::id())),
+        )
+    }
+
+    fn address() -> ContractAddress {
+        ContractAddress(HashOutput([7; 32]))
+    }
+
+    #[test]
+    fn staged_state_is_loaded_but_not_written() {
+        let store = store("staged");
+        let state = PrivateState::from([("key".to_string(), vec![1, 2, 3])]);
+        store.stage(&address(), "account", state.clone()).unwrap();
+
+        assert_eq!(store.load(&address(), "account").unwrap(), state);
+        assert!(!store.path(&address(), "account").exists());
+    }
+
+    #[test]
+    fn committed_state_survives_the_store() {
+        let dir = store("committed").dir;
+        let state = PrivateState::from([("key".to_string(), vec![1, 2, 3])]);
+        let store = PrivateStateStore::new(&dir);
+        store.stage(&address(), "account", state.clone()).unwrap();
+        store.commit().unwrap();
+
+        let reopened = PrivateStateStore::new(&dir);
+        assert_eq!(reopened.load(&address(), "account").unwrap(), state);
+    }
+
+    #[test]
+    fn unknown_accounts_start_empty() {
+        let store = store("empty");
+        assert!(store.load(&address(), "nobody").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,157 @@
+//! Deploys BBoard into a local ledger state and runs `post` and `takeDown`
+//! against it, applying the public transcripts `call_contract` would prove.
+//! Needs neither a node nor a prover; proving and fees are left to the
+//! binaries, as paying fees needs DUST only a chain can provide.
+
+#[path = "../src/bboard.rs"]
+mod bboard;
+#[path = "../src/keys.rs"]
+mod keys;
+#[path = "../src/witness.rs"]
+mod witness;
+
+use midnight_node_ledger_helpers::*;
+
+use rand::SeedableRng;
+use std::any::Any;
+use std::sync::Arc;
+
+use bboard::{BBoardContract, BBoardLedger, BoardState};
+
+fn seeded(seed: u8) -> StdRng {
+    StdRng::from_seed([seed; 32])
+}
+
+fn contract_state(
+    context: &LedgerContext<DefaultDB>,
+    address: ContractAddress,
+) -> ContractState<DefaultDB> {
+    context
+        .ledger_state
+        .lock()
+        .unwrap()
+        .index(address)
+        .expect("the deployed contract")
+}
+
+fn set_contract_state(
+    context: &LedgerContext<DefaultDB>,
+    address: ContractAddress,
+    contract: ContractState<DefaultDB>,
+) {
+    let mut state = context.ledger_state.lock().unwrap();
+    state.contract = state.contract.insert(address, contract);
+}
+
+fn board(context: &LedgerContext<DefaultDB>, address: ContractAddress) -> BBoardLedger {
+    BBoardLedger::decode(&contract_state(context, address).data.get()).unwrap()
+}
+
+/// Deploys BBoard into a fresh local ledger.
+async fn deploy() -> (Arc<LedgerContext<DefaultDB>>, ContractAddress) {
+    bboard::verifier_keys().unwrap();
+    let deploy = BBoardContract::new().deploy(&[], 0, &mut seeded(1)).await;
+    let context = Arc::new(LedgerContext::<DefaultDB>::new_from_wallet_seeds(
+        "undeployed",
+        &[],
+    ));
+    set_contract_state(&context, deploy.address(), deploy.initial_state.clone());
+    (context, deploy.address())
+}
+
+/// Checks and builds a call as `call_contract` does, then applies its
+/// transcripts to the contract's state.
+fn call(
+    context: &Arc<LedgerContext<DefaultDB>>,
+    address: ContractAddress,
+    bboard: &BBoardContract,
+    key: &'static str,
+    input: Box<dyn Any + Send + Sync>,
+) {
+    let state = contract_state(context, address);
+    bboard.check_call(key, &address, &state).unwrap();
+    let prototype = bboard.contract_call(&address, key, &input, &mut seeded(2), context.clone());
+
+    let mut query = QueryContext::new(state.data.clone(), address);
+    let transcripts = [
+        &prototype.guaranteed_public_transcript,
+        &prototype.fallible_public_transcript,
+    ];
+    for transcript in transcripts.into_iter().flatten() {
+        let program: Vec<_> = transcript.program.iter().map(|op| (*op).clone()).collect();
+        query = query
+            .query(&program, None, &INITIAL_COST_MODEL)
+            .unwrap()
+            .context;
+    }
+    set_contract_state(
+        context,
+        address,
+        ContractState {
+            data: query.state,
+            ..state
+        },
+    );
+}
+
+fn account(name: &str) -> BBoardContract {
+    BBoardContract::for_account(format!("bboard-flow-{name}")).with_rng(seeded(3))
+}
+
+#[tokio::test]
+async fn deploy_post_and_take_down() {
+    let (context, address) = deploy().await;
+    let initial = board(&context, address);
+    assert_eq!(initial.state, BoardState::Vacant);
+    assert_eq!(initial.sequence, 1);
+
+    let alice = account("alice");
+    call(
+        &context,
+        address,
+        &alice,
+        "post",
+        Box::new("hello".to_string()),
+    );
+    let posted = board(&context, address);
+    assert_eq!(posted.state, BoardState::Occupied);
+    assert_eq!(posted.message.as_deref(), Some("hello"));
+
+    let private_state = alice
+        .private_state
+        .load(&address, "bboard-flow-alice")
+        .unwrap();
+    let sk: [u8; 32] = private_state[bboard::SECRET_KEY]
+        .as_slice()
+        .try_into()
+        .unwrap();
+    assert_eq!(posted.owner, bboard::public_key(&sk, posted.sequence));
+
+    call(&context, address, &alice, "takeDown", Box::new(()));
+    let taken_down = board(&context, address);
+    assert_eq!(taken_down.state, BoardState::Vacant);
+    assert_eq!(taken_down.message, None);
+    assert_eq!(taken_down.sequence, 2);
+}
+
+#[tokio::test]
+async fn calls_that_would_fail_are_refused_up_front() {
+    let (context, address) = deploy().await;
+    let alice = account("alice");
+    let bob = account("bob");
+
+    let state = contract_state(&context, address);
+    assert!(bob.check_call("takeDown", &address, &state).is_err());
+    assert!(bob.check_call("vote", &address, &state).is_err());
+
+    call(
+        &context,
+        address,
+        &alice,
+        "post",
+        Box::new("hello".to_string()),
+    );
+    let state = contract_state(&context, address);
+    assert!(bob.check_call("post", &address, &state).is_err());
+    assert!(bob.check_call("takeDown", &address, &state).is_err());
+}
@@ -0,0 +1,107 @@
+//! Runs `proof_server_stub` and talks to it the way the proving binaries do.
+
+#[path = "../src/cli.rs"]
+mod cli;
+#[path = "../src/prover.rs"]
+mod prover;
+#[path = "../src/txfile.rs"]
+mod txfile;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::time::Duration;
+
+/// A running stub, killed on drop.
+struct Stub {
+    child: Child,
+    endpoint: String,
+}
+
+impl Stub {
+    fn start() -> Self {
+        let port = TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("a free port")
+            .port();
+        let listen = format!("127.0.0.1:{port}");
+        let child = Command::new(env!("CARGO_BIN_EXE_proof_server_stub"))
+            .args(["--listen", &listen, "--mock-proofs"])
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("proof_server_stub starts");
+
+        for _ in 0..100 {
+            if TcpStream::connect(&listen).is_ok() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Self {
+            child,
+            endpoint: format!("http://{listen}"),
+        }
+    }
+
+    fn post(&self, body: Vec<u8>) -> reqwest::RequestBuilder {
+        reqwest::Client::new()
+            .post(format!("{}/prove-tx", self.endpoint))
+            .body(body)
+    }
+}
+
+impl Drop for Stub {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[tokio::test]
+async fn from_args_selects_the_stub() {
+    let stub = Stub::start();
+    let args = cli::Args::parse(
+        ["--proof-server".to_string(), stub.endpoint.clone()],
+        prover::SWITCHES,
+    );
+    // `from_args` fails unless the stub answers its health check
+    prover::from_args(&args, "undeployed").await.unwrap();
+}
+
+#[tokio::test]
+async fn from_args_fails_without_a_server() {
+    let stub = Stub::start();
+    let endpoint = stub.endpoint.clone();
+    drop(stub);
+    let args = cli::Args::parse(["--proof-server".to_string(), endpoint], prover::SWITCHES);
+    assert!(prover::from_args(&args, "undeployed").await.is_err());
+}
+
+#[tokio::test]
+async fn undecodable_bodies_are_bad_requests() {
+    let stub = Stub::start();
+    let response = stub
+        .post(b"not a transaction".to_vec())
+        .send()
+        .await
+        .unwrap();
+    // 4xx, so `RemoteProofServer` does not retry
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+#[test]
+fn oversized_bodies_are_refused_unread() {
+    let stub = Stub::start();
+    let mut stream = TcpStream::connect(stub.endpoint.trim_start_matches("http://")).unwrap();
+    // Announces a terabyte and sends none of it; the stub must not wait for
+    // (or allocate) the body
+    write!(
+        stream,
+        "POST /prove-tx HTTP/1.1\r\ncontent-length: {}\r\n\r\n",
+        1u64 << 40
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 413"), "{response}");
+}